@@ -0,0 +1,53 @@
+// Copyright 2019-2020 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+mod types;
+
+pub use crate::types::{
+	Bond, CandidateInfo, CandidateMetadata, CandidateState, NominatorInfo, RoundInfo,
+};
+
+pub use rpc_impl_Stake::gen_server::Stake as StakeServer;
+
+/// Staking state queries, all methods take an optional block hash and default to the best block
+#[rpc(server)]
+pub trait Stake {
+	#[rpc(name = "stake_candidateInfo")]
+	fn candidate_info(&self, candidate: H160, at: Option<H256>) -> Result<Option<CandidateInfo>>;
+
+	#[rpc(name = "stake_roundInfo")]
+	fn round_info(&self, at: Option<H256>) -> Result<RoundInfo>;
+
+	#[rpc(name = "stake_nominations")]
+	fn nominations(&self, nominator: H160, at: Option<H256>) -> Result<Option<NominatorInfo>>;
+
+	#[rpc(name = "stake_pendingRewards")]
+	fn pending_rewards(&self, round: u32, account: H160, at: Option<H256>) -> Result<U256>;
+
+	#[rpc(name = "stake_minNomination")]
+	fn min_nomination(&self, validator: H160, at: Option<H256>) -> Result<Option<U256>>;
+
+	#[rpc(name = "stake_candidateMetadata")]
+	fn candidate_metadata(
+		&self,
+		candidate: H160,
+		at: Option<H256>,
+	) -> Result<Option<CandidateMetadata>>;
+}
@@ -0,0 +1,71 @@
+// Copyright 2019-2020 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethereum_types::{H160, U256};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Bond {
+	pub owner: H160,
+	pub amount: U256,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CandidateState {
+	Active,
+	Idle,
+	/// Round at which the exit is executed
+	Leaving(u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateInfo {
+	pub id: H160,
+	/// Commission in parts per billion
+	pub fee: u32,
+	pub bond: U256,
+	pub total: U256,
+	pub state: CandidateState,
+	pub nominators: Vec<Bond>,
+}
+
+/// Metadata published by a validator candidate, invalid UTF-8 is replaced
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateMetadata {
+	pub display_name: String,
+	pub website: String,
+	pub contact: String,
+	/// Free-form JSON
+	pub extra: String,
+	pub deposit: U256,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundInfo {
+	pub current: u32,
+	pub blocks_left: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NominatorInfo {
+	pub nominations: Vec<Bond>,
+	pub total: U256,
+}
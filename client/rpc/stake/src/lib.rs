@@ -0,0 +1,208 @@
+// Copyright 2019-2020 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::Result as RpcResult;
+use jsonrpc_core::{Error, ErrorCode};
+pub use moonbeam_rpc_core_stake::{
+	Bond, CandidateInfo, CandidateMetadata, CandidateState, NominatorInfo, RoundInfo,
+	Stake as StakeT, StakeServer,
+};
+use parity_scale_codec::Codec;
+use sp_api::{BlockId, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{traits::Block as BlockT, PerThing};
+use std::{marker::PhantomData, sync::Arc};
+
+use moonbeam_rpc_primitives_stake::{
+	Bond as StakeBond, CandidateMetadata as StakeCandidateMetadata, StakeRuntimeApi, Validator,
+	ValidatorStatus,
+};
+
+pub fn internal_err<T: ToString>(message: T) -> Error {
+	Error {
+		code: ErrorCode::InternalError,
+		message: message.to_string(),
+		data: None,
+	}
+}
+
+pub struct Stake<B: BlockT, C, AccountId, Balance> {
+	client: Arc<C>,
+	_marker: PhantomData<(B, AccountId, Balance)>,
+}
+
+impl<B: BlockT, C, AccountId, Balance> Stake<B, C, AccountId, Balance> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self {
+			client,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C, AccountId, Balance> Stake<B, C, AccountId, Balance>
+where
+	C: HeaderBackend<B>,
+	B: BlockT<Hash = H256>,
+{
+	fn block_id(&self, at: Option<H256>) -> BlockId<B> {
+		BlockId::Hash(at.unwrap_or_else(|| self.client.info().best_hash))
+	}
+}
+
+fn into_bonds<AccountId, Balance>(bonds: Vec<StakeBond<AccountId, Balance>>) -> Vec<Bond>
+where
+	AccountId: Into<H160>,
+	Balance: Into<U256>,
+{
+	bonds
+		.into_iter()
+		.map(|x| Bond {
+			owner: x.owner.into(),
+			amount: x.amount.into(),
+		})
+		.collect()
+}
+
+fn into_candidate_info<AccountId, Balance>(
+	candidate: Validator<AccountId, Balance>,
+) -> CandidateInfo
+where
+	AccountId: Into<H160>,
+	Balance: Into<U256>,
+{
+	CandidateInfo {
+		id: candidate.id.into(),
+		fee: candidate.fee.deconstruct(),
+		bond: candidate.bond.into(),
+		total: candidate.total.into(),
+		state: match candidate.state {
+			ValidatorStatus::Active => CandidateState::Active,
+			ValidatorStatus::Idle => CandidateState::Idle,
+			ValidatorStatus::Leaving(round) => CandidateState::Leaving(round),
+		},
+		nominators: into_bonds(candidate.nominators.0),
+	}
+}
+
+fn into_candidate_metadata<Balance>(metadata: StakeCandidateMetadata<Balance>) -> CandidateMetadata
+where
+	Balance: Into<U256>,
+{
+	let text = |bytes: Vec<u8>| String::from_utf8_lossy(&bytes).into_owned();
+	CandidateMetadata {
+		display_name: text(metadata.display_name),
+		website: text(metadata.website),
+		contact: text(metadata.contact),
+		extra: text(metadata.extra),
+		deposit: metadata.deposit.into(),
+	}
+}
+
+impl<B, C, AccountId, Balance> StakeT for Stake<B, C, AccountId, Balance>
+where
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C: Send + Sync + 'static,
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C::Api: StakeRuntimeApi<B, AccountId, Balance>,
+	AccountId: Codec + From<H160> + Into<H160> + Send + Sync + 'static,
+	Balance: Codec + Into<U256> + Send + Sync + 'static,
+{
+	fn candidate_info(
+		&self,
+		candidate: H160,
+		at: Option<H256>,
+	) -> RpcResult<Option<CandidateInfo>> {
+		let candidate = self
+			.client
+			.runtime_api()
+			.candidate_info(&self.block_id(at), candidate.into())
+			.map_err(|err| {
+				internal_err(format!("fetch runtime candidate info failed: {:?}", err))
+			})?;
+		Ok(candidate.map(into_candidate_info))
+	}
+
+	fn round_info(&self, at: Option<H256>) -> RpcResult<RoundInfo> {
+		let progress = self
+			.client
+			.runtime_api()
+			.round_progress(&self.block_id(at))
+			.map_err(|err| {
+				internal_err(format!("fetch runtime round progress failed: {:?}", err))
+			})?;
+		Ok(RoundInfo {
+			current: progress.current,
+			blocks_left: progress.blocks_left,
+		})
+	}
+
+	fn nominations(
+		&self,
+		nominator: H160,
+		at: Option<H256>,
+	) -> RpcResult<Option<NominatorInfo>> {
+		let nominator = self
+			.client
+			.runtime_api()
+			.nominations(&self.block_id(at), nominator.into())
+			.map_err(|err| {
+				internal_err(format!("fetch runtime nominations failed: {:?}", err))
+			})?;
+		Ok(nominator.map(|x| NominatorInfo {
+			nominations: into_bonds(x.nominations.0),
+			total: x.total.into(),
+		}))
+	}
+
+	fn pending_rewards(&self, round: u32, account: H160, at: Option<H256>) -> RpcResult<U256> {
+		let pending = self
+			.client
+			.runtime_api()
+			.pending_rewards(&self.block_id(at), round, account.into())
+			.map_err(|err| {
+				internal_err(format!("fetch runtime pending rewards failed: {:?}", err))
+			})?;
+		Ok(pending.into())
+	}
+
+	fn min_nomination(&self, validator: H160, at: Option<H256>) -> RpcResult<Option<U256>> {
+		let min = self
+			.client
+			.runtime_api()
+			.min_nomination(&self.block_id(at), validator.into())
+			.map_err(|err| {
+				internal_err(format!("fetch runtime minimum nomination failed: {:?}", err))
+			})?;
+		Ok(min.map(Into::into))
+	}
+
+	fn candidate_metadata(
+		&self,
+		candidate: H160,
+		at: Option<H256>,
+	) -> RpcResult<Option<CandidateMetadata>> {
+		let metadata = self
+			.client
+			.runtime_api()
+			.candidate_metadata(&self.block_id(at), candidate.into())
+			.map_err(|err| {
+				internal_err(format!("fetch runtime candidate metadata failed: {:?}", err))
+			})?;
+		Ok(metadata.map(into_candidate_metadata))
+	}
+}
@@ -192,10 +192,33 @@ export const moonbeamDefinitions = {
           ideal: "Perbill",
           max: "Perbill",
         },
+        Expectations: {
+          _enum: {
+            Absolute: "RangeBalance",
+            Relative: "RangePerbill",
+          },
+        },
         InflationInfo: {
-          expect: "RangeBalance",
+          expect: "Expectations",
+          annual: "RangePerbill",
           round: "RangePerbill",
         },
+        StakingParams: {
+          blocks_per_round: "u32",
+          max_validators: "u32",
+          max_nominators_per_validator: "u32",
+          min_validator_stk: "Balance",
+          min_nominator_stk: "Balance",
+        },
+        RoundInfo: {
+          current: "RoundIndex",
+          first_block: "BlockNumber",
+          length: "u32",
+        },
+        UptimeInfo: {
+          eligible: "u32",
+          authored: "u32",
+        },
         OrderedSet: "Vec",
         Validator: {
           id: "AccountId",
@@ -204,6 +227,14 @@ export const moonbeamDefinitions = {
           nominators: "Vec<Bond>",
           total: "Balance",
           state: "ValidatorStatus",
+          joined: "RoundIndex",
+        },
+        CandidateMetadata: {
+          display_name: "Bytes",
+          website: "Bytes",
+          contact: "Bytes",
+          extra: "Bytes",
+          deposit: "Balance",
         },
         ValidatorSnapshot: {
           fee: "Perbill",
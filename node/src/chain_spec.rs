@@ -16,16 +16,16 @@
 
 use cumulus_primitives::ParaId;
 use moonbeam_runtime::{
-	AccountId, Balance, BalancesConfig, DemocracyConfig, EVMConfig, EthereumChainIdConfig,
-	EthereumConfig, GenesisConfig, ParachainInfoConfig, SchedulerConfig, StakeConfig, SudoConfig,
-	SystemConfig, GLMR, WASM_BINARY,
+	AccountId, Balance, BalancesConfig, BlockTime, DemocracyConfig, EVMConfig,
+	EthereumChainIdConfig, EthereumConfig, GenesisConfig, ParachainInfoConfig, SchedulerConfig,
+	StakeConfig, SudoConfig, SystemConfig, GLMR, WASM_BINARY,
 };
 use sc_chain_spec::{ChainSpecExtension, ChainSpecGroup};
 use sc_service::ChainType;
 use sc_telemetry::TelemetryEndpoints;
 use serde::{Deserialize, Serialize};
 use sp_runtime::Perbill;
-use stake::{InflationInfo, Range};
+use stake::{Expectations, InflationInfo, Range, StakingParams};
 use std::{collections::BTreeMap, str::FromStr};
 
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
@@ -62,8 +62,10 @@ pub fn development_chain_spec() -> ChainSpec {
 					AccountId::from_str("6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b").unwrap(),
 					None,
 					1_000 * GLMR,
+					None,
 				)],
 				moonbeam_inflation_config(),
+				moonbeam_staking_params(),
 				vec![AccountId::from_str("6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b").unwrap()],
 				Default::default(), // para_id
 				1281,               //ChainId
@@ -101,8 +103,10 @@ pub fn get_chain_spec(para_id: ParaId) -> ChainSpec {
 					AccountId::from_str("6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b").unwrap(),
 					None,
 					1_000 * GLMR,
+					None,
 				)],
 				moonbeam_inflation_config(),
+				moonbeam_staking_params(),
 				vec![AccountId::from_str("6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b").unwrap()],
 				para_id,
 				1280, //ChainId
@@ -120,25 +124,38 @@ pub fn get_chain_spec(para_id: ParaId) -> ChainSpec {
 }
 
 pub fn moonbeam_inflation_config() -> InflationInfo<Balance> {
-	InflationInfo {
-		expect: Range {
+	InflationInfo::new(
+		Range {
+			min: Perbill::from_percent(4),
+			ideal: Perbill::from_percent(5),
+			max: Perbill::from_percent(5),
+		},
+		Expectations::Absolute(Range {
 			min: 100_000 * GLMR,
 			ideal: 200_000 * GLMR,
 			max: 500_000 * GLMR,
-		},
-		// 8766 rounds (hours) in a year
-		round: Range {
-			min: Perbill::from_parts(Perbill::from_percent(4).deconstruct() / 8766),
-			ideal: Perbill::from_parts(Perbill::from_percent(5).deconstruct() / 8766),
-			max: Perbill::from_parts(Perbill::from_percent(5).deconstruct() / 8766),
-		},
+		}),
+		moonbeam_staking_params().blocks_per_round,
+		BlockTime::get(),
+	)
+}
+
+pub fn moonbeam_staking_params() -> StakingParams<Balance> {
+	StakingParams {
+		// a new round every hour (600 * block_time)
+		blocks_per_round: 600,
+		max_validators: 8,
+		max_nominators_per_validator: 10,
+		min_validator_stk: 1_000 * GLMR,
+		min_nominator_stk: 5 * GLMR,
 	}
 }
 
 fn testnet_genesis(
 	root_key: AccountId,
-	stakers: Vec<(AccountId, Option<AccountId>, Balance)>,
+	stakers: Vec<(AccountId, Option<AccountId>, Balance, Option<Perbill>)>,
 	inflation_config: InflationInfo<Balance>,
+	params: StakingParams<Balance>,
 	endowed_accounts: Vec<AccountId>,
 	para_id: ParaId,
 	chain_id: u64,
@@ -171,6 +188,7 @@ fn testnet_genesis(
 		stake: Some(StakeConfig {
 			stakers,
 			inflation_config,
+			params,
 		}),
 	}
 }
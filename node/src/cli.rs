@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
-use sp_core::H160;
+use sp_core::{H160, H256};
 use std::path::PathBuf;
 use std::str::FromStr;
 use structopt::StructOpt;
@@ -50,6 +50,15 @@ pub enum Subcommand {
 
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
+
+	/// Benchmark the runtime pallets, requires the node to be built with
+	/// `--features runtime-benchmarks`.
+	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
+	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+	/// Check the invariants of the staking state in the database.
+	#[structopt(name = "check-stake", about = "Check the consistency of the staking state.")]
+	CheckStake(CheckStakeCmd),
 }
 
 /// Command for exporting the genesis state of the parachain
@@ -72,6 +81,32 @@ pub struct ExportGenesisStateCommand {
 	pub chain: Option<String>,
 }
 
+/// Command for checking the invariants of the staking state at a block of the database.
+#[derive(Debug, StructOpt)]
+pub struct CheckStakeCmd {
+	/// Hash of the block to check, the best block if unspecified.
+	#[structopt(long)]
+	pub at: Option<H256>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: sc_cli::ImportParams,
+}
+
+impl sc_cli::CliConfiguration for CheckStakeCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&sc_cli::ImportParams> {
+		Some(&self.import_params)
+	}
+}
+
 /// Command for exporting the genesis wasm file.
 #[derive(Debug, StructOpt)]
 pub struct ExportGenesisWasmCommand {
@@ -107,7 +142,8 @@ pub struct RunCmd {
 	#[structopt(long, default_value = "instant")]
 	pub sealing: Sealing,
 
-	/// Public identity for participating in staking and receiving rewards
+	/// Public identity for participating in staking and receiving rewards, the author key set
+	/// with `set_author_key` or the stash of the validator if it did not set one
 	#[structopt(long, parse(try_from_str = parse_h160))]
 	pub author_id: Option<H160>,
 }
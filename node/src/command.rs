@@ -20,7 +20,8 @@ use crate::{
 };
 use cumulus_primitives::{genesis::generate_genesis_block, ParaId};
 use log::info;
-use moonbeam_runtime::{AccountId, Block};
+use moonbeam_rpc_primitives_stake::StakeRuntimeApi;
+use moonbeam_runtime::{AccountId, Balance, Block};
 use parity_scale_codec::Encode;
 use polkadot_parachain::primitives::AccountIdConversion;
 use polkadot_service::RococoChainSpec;
@@ -32,10 +33,12 @@ use sc_service::{
 	config::{BasePath, PrometheusConfig},
 	PartialComponents,
 };
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
 use sp_core::hexdisplay::HexDisplay;
-use sp_core::H160;
-use sp_runtime::traits::Block as _;
-use std::{io::Write, net::SocketAddr, str::FromStr};
+use sp_core::{H160, H256};
+use sp_runtime::{generic::BlockId, traits::Block as _};
+use std::{io::Write, net::SocketAddr, str::FromStr, sync::Arc};
 
 fn load_spec(
 	id: &str,
@@ -160,6 +163,28 @@ fn extract_genesis_wasm(chain_spec: &Box<dyn sc_service::ChainSpec>) -> Result<V
 		.ok_or_else(|| "Could not find wasm file in genesis state!".into())
 }
 
+/// Check the invariants of the staking state at the block `at`, or at the best block
+fn check_stake<C>(client: Arc<C>, at: Option<H256>) -> Result<()>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: StakeRuntimeApi<Block, AccountId, Balance>,
+{
+	let at = at.unwrap_or_else(|| client.info().best_hash);
+	client
+		.runtime_api()
+		.try_state(&BlockId::Hash(at))
+		.map_err(|e| format!("Failed to check the staking state: {:?}", e))?
+		.map_err(|e| {
+			format!(
+				"Staking state at {} is inconsistent: {}",
+				at,
+				String::from_utf8_lossy(&e)
+			)
+		})?;
+	info!("Staking state at {} is consistent", at);
+	Ok(())
+}
+
 /// Parse command line arguments into service configuration.
 pub fn run() -> Result<()> {
 	let cli = Cli::from_args();
@@ -230,6 +255,24 @@ pub fn run() -> Result<()> {
 				Ok((cmd.run(client, backend), task_manager))
 			})
 		}
+		Some(Subcommand::Benchmark(cmd)) => {
+			if cfg!(feature = "runtime-benchmarks") {
+				let runner = cli.create_runner(cmd)?;
+				runner.sync_run(|config| cmd.run::<Block, crate::service::Executor>(config))
+			} else {
+				Err("Benchmarking wasn't enabled when building the node. \
+				You can enable it with `--features runtime-benchmarks`."
+					.into())
+			}
+		}
+		Some(Subcommand::CheckStake(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } =
+					crate::service::new_partial(&config, None, false)?;
+				check_stake(client, cmd.at)
+			})
+		}
 		Some(Subcommand::ExportGenesisState(params)) => {
 			let mut builder = sc_cli::LoggerBuilder::new("");
 			builder.with_profiling(sc_tracing::TracingReceiver::Log, "");
@@ -76,6 +76,7 @@ where
 	A: ChainApi<Block = Block> + 'static,
 	C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
 	C::Api: moonbeam_rpc_primitives_txpool::TxPoolRuntimeApi<Block>,
+	C::Api: moonbeam_rpc_primitives_stake::StakeRuntimeApi<Block, AccountId, Balance>,
 	<C::Api as sp_api::ApiErrorExt>::Error: fmt::Debug,
 	P: TransactionPool<Block = Block> + 'static,
 {
@@ -83,6 +84,7 @@ where
 		EthApi, EthApiServer, EthFilterApi, EthFilterApiServer, EthPubSubApi, EthPubSubApiServer,
 		HexEncodedIdProvider, NetApi, NetApiServer, Web3Api, Web3ApiServer,
 	};
+	use moonbeam_rpc_stake::{Stake, StakeServer};
 	use moonbeam_rpc_txpool::{TxPool, TxPoolServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
@@ -145,6 +147,7 @@ where
 			Arc::new(subscription_task_executor),
 		),
 	)));
+	io.extend_with(StakeServer::to_delegate(Stake::new(client.clone())));
 	io.extend_with(TxPoolServer::to_delegate(TxPool::new(client, pool)));
 
 	if let Some(command_sink) = command_sink {
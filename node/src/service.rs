@@ -47,6 +47,7 @@ native_executor_instance!(
 	pub Executor,
 	moonbeam_runtime::api::dispatch,
 	moonbeam_runtime::native_version,
+	frame_benchmarking::benchmarking::HostFunctions,
 );
 
 type FullClient = TFullClient<Block, RuntimeApi, Executor>;
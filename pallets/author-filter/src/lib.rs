@@ -58,16 +58,12 @@ pub mod pallet {
 		type RandomnessSource: Randomness<H256>;
 	}
 
-	// This code will be called by the author-inherent pallet to check whether the reported author
-	// of this block is eligible at this height. We calculate that result on demand and do not
-	// record it instorage (although we do emit a debugging event for now).
-	// This implementation relies on the relay parent's block number from the validation data
-	// inherent. Therefore this implementation must not be used as a preliminary check (only final)
-	// Further, the validation data inherent **must** be included before this check is
-	// performed. Concretely the validation data inherent must be included before the author
-	// inherent.
-	impl<T: Config> author_inherent::CanAuthor<T::AccountId> for Pallet<T> {
-		fn can_author(account: &T::AccountId) -> bool {
+	impl<T: Config> Pallet<T> {
+		/// The authors eligible at this height, a pseudorandom subset of the staked validators.
+		/// This implementation relies on the relay parent's block number from the validation data
+		/// inherent. Therefore the validation data inherent **must** be included before this is
+		/// called.
+		pub fn eligible_authors() -> Vec<T::AccountId> {
 			let mut staked: Vec<T::AccountId> = stake::Module::<T>::validators();
 
 			let num_eligible = EligibleRatio::<T>::get().mul_ceil(staked.len());
@@ -100,10 +96,6 @@ pub mod pallet {
 				let index = (randomness.to_low_u64_be() as u32) as usize;
 
 				// Move the selected author from the original vector into the eligible vector
-				// TODO we could short-circuit this check by returning early when the claimed
-				// author is selected. For now I'll leave it like this because:
-				// 1. it is easier to understand what our core filtering logic is
-				// 2. we currently show the entire filtered set in the debug event
 				eligible.push(staked.remove(index % staked.len()));
 
 				// Print some logs for debugging purposes.
@@ -123,34 +115,66 @@ pub mod pallet {
 					"Eligible Authors are: {:?}",
 					eligible
 				);
-				debug::trace!(
-					target:"author-filter",
-					"The id I'm checking is: {:?}",
-					account
-				);
-				debug::trace!(
-					target:"author-filter",
-					"Was that author eligible: {}",
-					eligible.contains(account)
-				);
 			}
 
 			// Emit an event for debugging purposes
 			// let our_height = frame_system::Module::<T>::block_number();
 			// <Pallet<T>>::deposit_event(Event::Filtered(our_height, relay_height, eligible.clone()));
 
-			eligible.contains(account)
+			eligible
+		}
+	}
+
+	// This code will be called by the author-inherent pallet to check whether the reported author
+	// of this block is eligible at this height. We calculate that result on demand, without
+	// writing to storage. This implementation relies on the validation data inherent (see
+	// `eligible_authors`). Therefore this implementation must not be used as a preliminary check
+	// (only final). Concretely the validation data inherent must be included before the author
+	// inherent.
+	impl<T: Config> author_inherent::CanAuthor<T::AccountId> for Pallet<T> {
+		fn can_author(account: &T::AccountId) -> bool {
+			let eligible = Self::eligible_authors();
+
+			debug::trace!(
+				target:"author-filter",
+				"The id I'm checking is: {:?}",
+				account
+			);
+
+			// The account is the author key of a validator, whose stash is the one selected
+			let can_author = stake::Module::<T>::stash_of_author(account)
+				.map_or(false, |stash| eligible.contains(&stash));
+			debug::trace!(
+				target:"author-filter",
+				"Was that author eligible: {}",
+				can_author
+			);
+			can_author
 		}
 	}
 
-	// No hooks
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: T::BlockNumber) -> Weight {
+			// The ratio is read here and again in `on_finalize`, along with the validators, the
+			// validation data, the round and the uptime of every eligible author, which is written
+			let max_validators = <T as stake::Config>::MaxValidators::get() as usize;
+			let eligible = EligibleRatio::<T>::get().mul_ceil(max_validators) as Weight;
+			T::DbWeight::get().reads_writes(5 + eligible, eligible)
+		}
+		// Count the slot for every author eligible at this height in its uptime for the round,
+		// once per block. Hooks are executed in the reverse order the pallets are declared in
+		// the runtime, so this pallet is declared after the stake pallet to record the slot before
+		// the round changes.
+		fn on_finalize(_n: T::BlockNumber) {
+			stake::Module::<T>::note_eligible(&Self::eligible_authors());
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Update the eligible ratio. Intended to be called by governance.
-		#[pallet::weight(0)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
 		pub fn set_eligible(origin: OriginFor<T>, new: Percent) -> DispatchResultWithPostInfo {
 			ensure_root(origin)?;
 			EligibleRatio::<T>::put(&new);
@@ -23,7 +23,7 @@
 use frame_support::debug;
 use frame_support::{
 	decl_error, decl_module, decl_storage, ensure,
-	traits::FindAuthor,
+	traits::{FindAuthor, Get},
 	weights::{DispatchClass, Weight},
 };
 use frame_system::{ensure_none, Config as System};
@@ -96,12 +96,15 @@ decl_module! {
 
 		fn on_initialize() -> Weight {
 			<Author<T>>::kill();
-			0
+			T::DbWeight::get().writes(1)
 		}
 
 		/// Inherent to set the author of a block
+		// The author checks and the event handlers read the staked and eligible authors, the
+		// relay chain height, the randomness and the reward points; the author, the digest and
+		// the reward points are written.
 		#[weight = (
-			0,
+			T::DbWeight::get().reads_writes(8, 4),
 			DispatchClass::Mandatory
 		)]
 		fn set_author(origin, author: T::AccountId) {
@@ -0,0 +1,804 @@
+// Copyright 2019-2020 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks for stake, see `weights.rs` for the components
+#![cfg(feature = "runtime-benchmarks")]
+
+use crate::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::OnFinalize;
+use frame_system::RawOrigin;
+use sp_std::vec;
+
+const SEED: u32 = 0;
+/// Upper bound on the number of slashes cancelled in one call for benchmarking purposes
+const MAX_SLASHES: u32 = 100;
+/// Upper bound on the number of exits, unbonds and deferred slashes executed in a round transition
+/// for benchmarking purposes
+const MAX_QUEUED: u32 = 20;
+
+/// Raise the staking parameters to the `Config` upper bounds so that every bounded set can be
+/// filled by the benchmarks
+fn raise_params_to_bounds<T: Config>() {
+	<Params<T>>::mutate(|params| {
+		params.max_validators = T::MaxValidators::get();
+		params.max_nominators_per_validator = T::MaxNominatorsPerValidator::get();
+	});
+}
+
+fn min_validator_stk<T: Config>() -> BalanceOf<T> {
+	<Params<T>>::get().min_validator_stk
+}
+
+fn min_nominator_stk<T: Config>() -> BalanceOf<T> {
+	<Params<T>>::get().min_nominator_stk
+}
+
+/// Enough balance to bond as a candidate, publish metadata and nominate many times over
+fn endowment<T: Config>() -> BalanceOf<T> {
+	(min_validator_stk::<T>() + min_nominator_stk::<T>()) * 1_000u32.into()
+		+ T::MetadataDeposit::get()
+}
+
+fn funded<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let acc: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&acc, endowment::<T>());
+	acc
+}
+
+fn create_candidate<T: Config>(index: u32) -> T::AccountId {
+	raise_params_to_bounds::<T>();
+	let acc = funded::<T>("candidate", index);
+	Pallet::<T>::join_candidates(
+		RawOrigin::Signed(acc.clone()).into(),
+		Perbill::zero(),
+		min_validator_stk::<T>(),
+	)
+	.expect("funded account can join candidates");
+	acc
+}
+
+fn create_nominator<T: Config>(index: u32, validator: T::AccountId) -> T::AccountId {
+	let acc = funded::<T>("nominator", index);
+	Pallet::<T>::join_nominators(
+		RawOrigin::Signed(acc.clone()).into(),
+		validator,
+		min_nominator_stk::<T>(),
+	)
+	.expect("funded account can join nominators");
+	acc
+}
+
+/// Create a candidate with `n` nominators
+fn create_nominated_candidate<T: Config>(index: u32, n: u32) -> T::AccountId {
+	let validator = create_candidate::<T>(index);
+	for i in 0..n {
+		create_nominator::<T>(index * T::MaxNominatorsPerValidator::get() + i, validator.clone());
+	}
+	validator
+}
+
+benchmarks! {
+	set_staking_expectations {
+		let stake = min_validator_stk::<T>();
+		let expectations = Expectations::Absolute(Range {
+			min: stake,
+			ideal: stake * 2u32.into(),
+			max: stake * 3u32.into(),
+		});
+		let origin = T::SetMonetaryPolicyOrigin::successful_origin();
+	}: _<T::Origin>(origin, expectations.clone())
+	verify {
+		assert_eq!(<InflationConfig<T>>::get().expect, expectations);
+	}
+
+	set_inflation {
+		let schedule = Range {
+			min: Perbill::from_percent(4),
+			ideal: Perbill::from_percent(5),
+			max: Perbill::from_percent(6),
+		};
+		let origin = T::SetMonetaryPolicyOrigin::successful_origin();
+	}: _<T::Origin>(origin, schedule.clone())
+	verify {
+		let length = <Round<T>>::get().length;
+		assert_eq!(
+			<InflationConfig<T>>::get().round,
+			inflation::annual_to_round(schedule, length, T::BlockTime::get())
+		);
+	}
+
+	set_issuance_curve {
+		let origin = T::SetMonetaryPolicyOrigin::successful_origin();
+	}: _<T::Origin>(origin, IssuanceCurve::PeakAtIdeal)
+	verify {
+		assert_eq!(<Curve<T>>::get(), IssuanceCurve::PeakAtIdeal);
+	}
+
+	set_parachain_bond_account {
+		let bond: T::AccountId = account("bond", 0, SEED);
+		let origin = T::SetMonetaryPolicyOrigin::successful_origin();
+	}: _<T::Origin>(origin, bond.clone())
+	verify {
+		assert_eq!(<ParachainBondInfo<T>>::get().account, bond);
+	}
+
+	set_parachain_bond_reserve_percent {
+		let origin = T::SetMonetaryPolicyOrigin::successful_origin();
+	}: _<T::Origin>(origin, Percent::from_percent(30))
+	verify {
+		assert_eq!(<ParachainBondInfo<T>>::get().percent, Percent::from_percent(30));
+	}
+
+	set_staking_params {
+		let mut params = Pallet::<T>::staking_params();
+		params.max_validators = T::MaxValidators::get();
+		params.max_nominators_per_validator = T::MaxNominatorsPerValidator::get();
+		let origin = T::SetMonetaryPolicyOrigin::successful_origin();
+	}: _<T::Origin>(origin, params.clone())
+	verify {
+		assert_eq!(<PendingParams<T>>::get(), Some(params));
+	}
+
+	join_candidates {
+		let caller = funded::<T>("caller", 0);
+	}: _(RawOrigin::Signed(caller.clone()), T::MaxFee::get(), min_validator_stk::<T>())
+	verify {
+		assert!(Pallet::<T>::is_candidate(&caller));
+	}
+
+	leave_candidates {
+		let caller = create_candidate::<T>(0);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(<Candidates<T>>::get(&caller).unwrap().is_leaving());
+	}
+
+	go_offline {
+		let caller = create_candidate::<T>(0);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(!<Candidates<T>>::get(&caller).unwrap().is_active());
+	}
+
+	go_online {
+		let caller = create_candidate::<T>(0);
+		Pallet::<T>::go_offline(RawOrigin::Signed(caller.clone()).into())?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(<Candidates<T>>::get(&caller).unwrap().is_active());
+	}
+
+	candidate_bond_more {
+		let caller = create_candidate::<T>(0);
+		let more = min_validator_stk::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), more)
+	verify {
+		assert_eq!(<Candidates<T>>::get(&caller).unwrap().bond, more + more);
+	}
+
+	candidate_bond_less {
+		let caller = funded::<T>("caller", 0);
+		let less = min_validator_stk::<T>();
+		Pallet::<T>::join_candidates(
+			RawOrigin::Signed(caller.clone()).into(),
+			Perbill::zero(),
+			less + less,
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), less)
+	verify {
+		assert_eq!(<Candidates<T>>::get(&caller).unwrap().bond, less);
+	}
+
+	set_commission {
+		let caller = create_candidate::<T>(0);
+		let when = <Round<T>>::get().current + T::CommissionChangeDelay::get();
+	}: _(RawOrigin::Signed(caller.clone()), T::MaxFee::get())
+	verify {
+		assert_eq!(<PendingCommission<T>>::get(&caller), Some((T::MaxFee::get(), when)));
+	}
+
+	set_metadata {
+		let caller = create_candidate::<T>(0);
+		let field = vec![b'x'; T::MaxMetadataFieldLength::get() as usize];
+		let extra = vec![b'x'; T::MaxMetadataExtraLength::get() as usize];
+	}: _(RawOrigin::Signed(caller.clone()), field.clone(), field.clone(), field, extra)
+	verify {
+		assert!(<Metadata<T>>::contains_key(&caller));
+	}
+
+	clear_metadata {
+		let caller = create_candidate::<T>(0);
+		Pallet::<T>::set_metadata(
+			RawOrigin::Signed(caller.clone()).into(),
+			b"name".to_vec(),
+			b"website".to_vec(),
+			b"contact".to_vec(),
+			b"{}".to_vec(),
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(!<Metadata<T>>::contains_key(&caller));
+	}
+
+	set_controller {
+		let caller = create_candidate::<T>(0);
+		let old: T::AccountId = account("controller", 0, SEED);
+		Pallet::<T>::set_controller(RawOrigin::Signed(caller.clone()).into(), old)?;
+		let controller: T::AccountId = account("controller", 1, SEED);
+	}: _(RawOrigin::Signed(caller.clone()), controller.clone())
+	verify {
+		assert_eq!(<Controllers<T>>::get(&caller), Some(controller));
+	}
+
+	set_author_key {
+		let caller = create_candidate::<T>(0);
+		let old: T::AccountId = account("author", 0, SEED);
+		Pallet::<T>::set_author_key(RawOrigin::Signed(caller.clone()).into(), old)?;
+		let author: T::AccountId = account("author", 1, SEED);
+	}: _(RawOrigin::Signed(caller.clone()), author.clone())
+	verify {
+		assert_eq!(<AuthorKeys<T>>::get(&caller), Some(author));
+	}
+
+	join_nominators {
+		// the validator is full for the largest `n`, the caller then evicts its lowest nomination
+		let n in 0 .. T::MaxNominatorsPerValidator::get();
+		let validator = create_nominated_candidate::<T>(0, n);
+		let caller = funded::<T>("caller", 0);
+		let amount = min_nominator_stk::<T>() * 2u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), validator.clone(), amount)
+	verify {
+		assert!(Pallet::<T>::is_nominator(&caller));
+		assert_eq!(
+			<Candidates<T>>::get(&validator).unwrap().nominators.0.len() as u32,
+			(n + 1).min(T::MaxNominatorsPerValidator::get())
+		);
+	}
+
+	leave_nominators {
+		let v in 1 .. T::MaxValidatorsPerNominator::get();
+		let n in 1 .. T::MaxNominatorsPerValidator::get();
+		// the caller nominates `v` validators that each have `n` nominators including the caller
+		let caller = funded::<T>("caller", 0);
+		for i in 0..v {
+			let validator = create_nominated_candidate::<T>(i, n - 1);
+			let origin = RawOrigin::Signed(caller.clone()).into();
+			if i == 0 {
+				Pallet::<T>::join_nominators(origin, validator, min_nominator_stk::<T>())?;
+			} else {
+				Pallet::<T>::nominate_new(origin, validator, T::MinNomination::get())?;
+			}
+		}
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(!Pallet::<T>::is_nominator(&caller));
+	}
+
+	nominate_new {
+		// the validator is full for the largest `n`, the caller then evicts its lowest nomination
+		let n in 0 .. T::MaxNominatorsPerValidator::get();
+		let caller = funded::<T>("caller", 0);
+		let first = create_candidate::<T>(0);
+		Pallet::<T>::join_nominators(
+			RawOrigin::Signed(caller.clone()).into(),
+			first,
+			min_nominator_stk::<T>(),
+		)?;
+		let validator = create_nominated_candidate::<T>(1, n);
+		let amount = min_nominator_stk::<T>() * 2u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), validator.clone(), amount)
+	verify {
+		assert_eq!(
+			<Candidates<T>>::get(&validator).unwrap().nominators.0.len() as u32,
+			(n + 1).min(T::MaxNominatorsPerValidator::get())
+		);
+	}
+
+	switch_nomination {
+		// the new validator is full for the largest `n`, the caller then evicts its lowest
+		// nomination
+		let n in 0 .. T::MaxNominatorsPerValidator::get();
+		let caller = funded::<T>("caller", 0);
+		let old = create_candidate::<T>(0);
+		Pallet::<T>::join_nominators(
+			RawOrigin::Signed(caller.clone()).into(),
+			old.clone(),
+			min_nominator_stk::<T>() * 2u32.into(),
+		)?;
+		let new = create_nominated_candidate::<T>(1, n);
+	}: _(RawOrigin::Signed(caller.clone()), old, new.clone())
+	verify {
+		assert_eq!(
+			<Candidates<T>>::get(&new).unwrap().nominators.0.len() as u32,
+			(n + 1).min(T::MaxNominatorsPerValidator::get())
+		);
+	}
+
+	revoke_nomination {
+		let n in 1 .. T::MaxNominatorsPerValidator::get();
+		let caller = funded::<T>("caller", 0);
+		let first = create_candidate::<T>(0);
+		Pallet::<T>::join_nominators(
+			RawOrigin::Signed(caller.clone()).into(),
+			first,
+			min_nominator_stk::<T>(),
+		)?;
+		let validator = create_nominated_candidate::<T>(1, n - 1);
+		Pallet::<T>::nominate_new(
+			RawOrigin::Signed(caller.clone()).into(),
+			validator.clone(),
+			T::MinNomination::get(),
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), validator.clone())
+	verify {
+		assert_eq!(<Candidates<T>>::get(&validator).unwrap().nominators.0.len() as u32, n - 1);
+	}
+
+	nominator_bond_more {
+		let n in 1 .. T::MaxNominatorsPerValidator::get();
+		let validator = create_nominated_candidate::<T>(0, n - 1);
+		let caller = funded::<T>("caller", 0);
+		let bond = min_nominator_stk::<T>();
+		Pallet::<T>::join_nominators(
+			RawOrigin::Signed(caller.clone()).into(),
+			validator.clone(),
+			bond,
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), validator, bond)
+	verify {
+		assert_eq!(<Nominators<T>>::get(&caller).unwrap().total, bond + bond);
+	}
+
+	nominator_bond_less {
+		let n in 1 .. T::MaxNominatorsPerValidator::get();
+		let validator = create_nominated_candidate::<T>(0, n - 1);
+		let caller = funded::<T>("caller", 0);
+		let less = min_nominator_stk::<T>();
+		Pallet::<T>::join_nominators(
+			RawOrigin::Signed(caller.clone()).into(),
+			validator.clone(),
+			less + less,
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), validator, less)
+	verify {
+		assert_eq!(<Nominators<T>>::get(&caller).unwrap().total, less);
+	}
+
+	set_auto_compound {
+		let validator = create_candidate::<T>(0);
+		let caller = create_nominator::<T>(0, validator);
+	}: _(RawOrigin::Signed(caller.clone()), Perbill::from_percent(50))
+	verify {
+		assert_eq!(<AutoCompound<T>>::get(&caller), Perbill::from_percent(50));
+	}
+
+	set_reward_destination {
+		let validator = create_candidate::<T>(0);
+		let caller = create_nominator::<T>(0, validator);
+		let payee: T::AccountId = account("payee", 0, SEED);
+		let destination = RewardDestination::Account(payee);
+	}: _(RawOrigin::Signed(caller.clone()), destination.clone())
+	verify {
+		assert_eq!(<Payee<T>>::get(&caller), destination);
+	}
+
+	claim_rewards {
+		let n in 0 .. T::MaxNominatorsPerValidator::get();
+		let validator = create_nominated_candidate::<T>(0, n);
+		// every reward is split between a new payee account and bonding back
+		let stakers = <Candidates<T>>::get(&validator)
+			.unwrap()
+			.nominators
+			.0
+			.into_iter()
+			.map(|bond| bond.owner)
+			.chain(sp_std::iter::once(validator.clone()));
+		for (i, staker) in stakers.enumerate() {
+			let payee: T::AccountId = account("payee", i as u32, SEED);
+			<Payee<T>>::insert(&staker, RewardDestination::Account(payee));
+			<AutoCompound<T>>::insert(&staker, Perbill::from_percent(50));
+		}
+		let round = <Round<T>>::get().current;
+		// snapshot the exposure of the validator and make all of its points claimable
+		Pallet::<T>::best_candidates_become_validators(round);
+		<AwardedPts<T>>::insert(round, &validator, 20);
+		<Points<T>>::insert(round, 20);
+		<RoundIssuance<T>>::insert(round, endowment::<T>());
+		let caller = funded::<T>("caller", 0);
+	}: _(RawOrigin::Signed(caller), round, validator.clone())
+	verify {
+		assert!(!<AwardedPts<T>>::contains_key(round, &validator));
+	}
+
+	report_offence {
+		let n in 0 .. T::MaxNominatorsPerValidator::get();
+		let validator = create_nominated_candidate::<T>(0, n);
+		let round = <Round<T>>::get().current;
+		// snapshot the exposure of the validator for the round of the offence
+		Pallet::<T>::best_candidates_become_validators(round);
+		let origin = T::ReportOrigin::successful_origin();
+	}: _<T::Origin>(origin, validator.clone(), round)
+	verify {
+		assert!(<Offenders<T>>::get(round, &validator));
+	}
+
+	cancel_deferred_slash {
+		let s in 1 .. MAX_SLASHES;
+		let round = <Round<T>>::get().current + T::SlashDeferDuration::get();
+		let slashes = (0..s)
+			.map(|i| UnappliedSlash {
+				validator: account("offender", i, SEED),
+				own: BalanceOf::<T>::zero(),
+				others: Vec::new(),
+			})
+			.collect::<Vec<UnappliedSlash<T::AccountId, BalanceOf<T>>>>();
+		<UnappliedSlashes<T>>::insert(round, slashes);
+		let origin = T::SlashCancelOrigin::successful_origin();
+	}: _<T::Origin>(origin, round, (0..s).collect())
+	verify {
+		assert!(<UnappliedSlashes<T>>::get(round).is_empty());
+	}
+
+	round_transition {
+		let v in 1 .. T::MaxValidators::get();
+		let n in 0 .. T::MaxNominatorsPerValidator::get();
+		let e in 0 .. MAX_QUEUED;
+		let u in 0 .. MAX_QUEUED;
+		let d in 0 .. MAX_QUEUED;
+		// the rewards of the round `RewardClaimWindow` rounds before `payout_round` expire in the
+		// transition
+		let payout_round = T::RewardClaimWindow::get() + 1;
+		let length = <Round<T>>::get().length;
+		<Round<T>>::put(RoundInfo::new(payout_round, 1u32.into(), length));
+		<RoundIssuance<T>>::insert(1, min_nominator_stk::<T>());
+		for i in 0..v {
+			let validator = create_nominated_candidate::<T>(i, n);
+			<AwardedPts<T>>::insert(payout_round, &validator, 20);
+		}
+		<Points<T>>::insert(payout_round, 20 * v);
+		<Staked<T>>::insert(payout_round, <Total<T>>::get());
+		<ParachainBondInfo<T>>::put(ParachainBondConfig {
+			account: account("bond", 0, SEED),
+			percent: Percent::from_percent(30),
+		});
+		// snapshot the exposure of every validator for the round that becomes claimable
+		Pallet::<T>::best_candidates_become_validators(payout_round);
+		// every validator changes its commission in the transition
+		let next = payout_round + T::BondDuration::get();
+		for validator in <Validators<T>>::get() {
+			<PendingCommission<T>>::insert(&validator, (T::MaxFee::get(), next));
+			<ScheduledCommissions<T>>::mutate(next, |queue| queue.push(validator));
+		}
+		// every validator was eligible in the ending round, every other one authored blocks and is
+		// awarded uptime points, the others authored none and are made idle
+		let ending = next - 1;
+		let mut missed = vec![];
+		for (i, validator) in <Validators<T>>::get().into_iter().enumerate() {
+			let authored = if i % 2 == 0 { 0 } else { 10 };
+			<Uptime<T>>::insert(ending, &validator, UptimeInfo { eligible: 20, authored });
+			if authored == 0 {
+				missed.push((validator, T::MaxMissedRounds::get().saturating_sub(1)));
+			}
+		}
+		<MissedRounds<T>>::put(missed);
+		// `e` candidates with `n` nominators each exit in the transition, `BondDuration` rounds
+		// after `payout_round`
+		for i in 0..e {
+			let candidate = create_nominated_candidate::<T>(v + i, n);
+			Pallet::<T>::leave_candidates(RawOrigin::Signed(candidate).into())?;
+		}
+		// the stake of `u` nominators finishes unbonding in the transition
+		let validator = <Validators<T>>::get()[0].clone();
+		for i in 0..u {
+			let nominator = funded::<T>("unbonding", i);
+			let amount = min_nominator_stk::<T>();
+			T::Currency::reserve(&nominator, amount)?;
+			<Unbonding<T>>::insert(
+				&nominator,
+				vec![UnbondingChunk {
+					validator: validator.clone(),
+					amount,
+					when: next,
+				}],
+			);
+			<UnbondingQueue<T>>::mutate(next, |queue| queue.push(nominator));
+		}
+		// `d` slashes of validators with `n` nominators each were deferred until the transition
+		let fraction = T::SlashFraction::get();
+		let slashes = <Validators<T>>::get()
+			.iter()
+			.cycle()
+			.take(d as usize)
+			.map(|validator| {
+				let exposure = <AtStake<T>>::get(payout_round, validator);
+				UnappliedSlash {
+					validator: validator.clone(),
+					own: fraction * exposure.bond,
+					others: exposure
+						.nominators
+						.into_iter()
+						.map(|x| Bond {
+							owner: x.owner,
+							amount: fraction * x.amount,
+						})
+						.collect(),
+				}
+			})
+			.collect::<Vec<UnappliedSlash<T::AccountId, BalanceOf<T>>>>();
+		<UnappliedSlashes<T>>::insert(next, slashes);
+		// the transition out of this round makes `payout_round` claimable
+		let mut params = Pallet::<T>::staking_params();
+		let round = RoundInfo::new(ending, 1u32.into(), params.blocks_per_round);
+		<Round<T>>::put(round);
+		// the round length scheduled by governance changes in the transition
+		params.blocks_per_round += 1;
+		<PendingParams<T>>::put(params);
+		let block = round.last_block();
+	}: { Pallet::<T>::on_finalize(block); }
+	verify {
+		assert_eq!(<Round<T>>::get().current, payout_round + T::BondDuration::get());
+		assert_eq!(<Round<T>>::get().length, round.length + 1);
+		assert!(<RoundIssuance<T>>::contains_key(payout_round));
+		assert!(<ScheduledCommissions<T>>::get(payout_round + T::BondDuration::get()).is_empty());
+		assert!(<PendingParams<T>>::get().is_none());
+		assert!(<AwardedPts<T>>::iter_prefix(ending).count() >= (v / 2) as usize);
+		assert!(<ExitQueue<T>>::get().0.is_empty());
+		assert!(<UnbondingQueue<T>>::get(next).is_empty());
+		assert!(<UnappliedSlashes<T>>::get(next).is_empty());
+		assert!(!<RoundIssuance<T>>::contains_key(1));
+	}
+
+	prune_history {
+		let p in 0 .. T::MaxPrunedPerBlock::get();
+		// `p` entries of the history of the oldest round are older than `HistoryDepth` rounds
+		let oldest = <OldestRound<T>>::get();
+		for i in 0..p {
+			let validator: T::AccountId = account("validator", i, SEED);
+			<AwardedPts<T>>::insert(oldest, &validator, 20);
+		}
+		let length = <Round<T>>::get().length;
+		let now = oldest + T::HistoryDepth::get() + 1;
+		<Round<T>>::put(RoundInfo::new(now, 1u32.into(), length));
+	}: { Pallet::<T>::prune_history(); }
+	verify {
+		assert_eq!(<AwardedPts<T>>::iter_prefix(oldest).count(), 0);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{no_stakers, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn bench_set_staking_expectations() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_staking_expectations::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_set_inflation() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_inflation::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_set_issuance_curve() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_issuance_curve::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_set_parachain_bond_account() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_parachain_bond_account::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_set_parachain_bond_reserve_percent() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_parachain_bond_reserve_percent::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_set_staking_params() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_staking_params::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_join_candidates() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_join_candidates::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_leave_candidates() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_leave_candidates::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_go_offline() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_go_offline::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_go_online() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_go_online::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_candidate_bond_more() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_candidate_bond_more::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_candidate_bond_less() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_candidate_bond_less::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_set_commission() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_commission::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_set_metadata() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_metadata::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_clear_metadata() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_clear_metadata::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_set_controller() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_controller::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_set_author_key() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_author_key::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_join_nominators() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_join_nominators::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_leave_nominators() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_leave_nominators::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_nominate_new() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_nominate_new::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_switch_nomination() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_switch_nomination::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_revoke_nomination() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_revoke_nomination::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_nominator_bond_more() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_nominator_bond_more::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_nominator_bond_less() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_nominator_bond_less::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_set_auto_compound() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_auto_compound::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_set_reward_destination() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_set_reward_destination::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_claim_rewards() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_claim_rewards::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_report_offence() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_report_offence::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_cancel_deferred_slash() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_cancel_deferred_slash::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_round_transition() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_round_transition::<Test>());
+		});
+	}
+
+	#[test]
+	fn bench_prune_history() {
+		no_stakers().execute_with(|| {
+			assert_ok!(test_benchmark_prune_history::<Test>());
+		});
+	}
+}
@@ -0,0 +1,217 @@
+// Copyright 2019-2020 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Property tests running random sequences of stake calls on the mock runtime
+//!
+//! After every call, the state must pass `do_try_state` and no funds may be created or destroyed
+//! other than the rewards minted and the stake slashed. Failing sequences are shrunk by proptest
+//! to a minimal reproduction.
+use crate::mock::*;
+use crate::*;
+use proptest::prelude::*;
+use sp_runtime::Perbill;
+
+/// Accounts 1 to 10 are funded by the genesis, 11 and 12 are not
+const ACCOUNTS: AccountId = 12;
+
+#[derive(Clone, Debug)]
+enum Action {
+	JoinCandidates(AccountId, u32, Balance),
+	LeaveCandidates(AccountId),
+	GoOffline(AccountId),
+	GoOnline(AccountId),
+	CandidateBondMore(AccountId, Balance),
+	CandidateBondLess(AccountId, Balance),
+	SetCommission(AccountId, u32),
+	JoinNominators(AccountId, AccountId, Balance),
+	NominateNew(AccountId, AccountId, Balance),
+	SwitchNomination(AccountId, AccountId, AccountId),
+	RevokeNomination(AccountId, AccountId),
+	LeaveNominators(AccountId),
+	NominatorBondMore(AccountId, AccountId, Balance),
+	NominatorBondLess(AccountId, AccountId, Balance),
+	SetAutoCompound(AccountId, u32),
+	SetRewardDestination(AccountId, RewardDestination<AccountId>),
+	/// Award points to the validator at this index of the current validator set
+	Author(usize, u32),
+	/// Claim the rewards of a validator for this many rounds ago
+	ClaimRewards(RoundIndex, AccountId),
+	/// Report an offence of a validator this many rounds ago
+	ReportOffence(RoundIndex, AccountId),
+	/// Finalize this many blocks
+	Roll(u64),
+}
+
+fn account() -> impl Strategy<Value = AccountId> {
+	1..=ACCOUNTS
+}
+
+fn amount() -> impl Strategy<Value = Balance> {
+	1..=60u128
+}
+
+fn destination() -> impl Strategy<Value = RewardDestination<AccountId>> {
+	prop_oneof![
+		Just(RewardDestination::Staker),
+		account().prop_map(RewardDestination::Account),
+		Just(RewardDestination::Restake),
+	]
+}
+
+fn candidate_action() -> impl Strategy<Value = Action> {
+	prop_oneof![
+		(account(), 0..=60u32, amount()).prop_map(|(a, f, b)| Action::JoinCandidates(a, f, b)),
+		account().prop_map(Action::LeaveCandidates),
+		account().prop_map(Action::GoOffline),
+		account().prop_map(Action::GoOnline),
+		(account(), amount()).prop_map(|(a, b)| Action::CandidateBondMore(a, b)),
+		(account(), amount()).prop_map(|(a, b)| Action::CandidateBondLess(a, b)),
+		(account(), 0..=60u32).prop_map(|(a, f)| Action::SetCommission(a, f)),
+	]
+}
+
+fn nominator_action() -> impl Strategy<Value = Action> {
+	prop_oneof![
+		(account(), account(), amount()).prop_map(|(a, v, b)| Action::JoinNominators(a, v, b)),
+		(account(), account(), amount()).prop_map(|(a, v, b)| Action::NominateNew(a, v, b)),
+		(account(), account(), account())
+			.prop_map(|(a, old, new)| Action::SwitchNomination(a, old, new)),
+		(account(), account()).prop_map(|(a, v)| Action::RevokeNomination(a, v)),
+		account().prop_map(Action::LeaveNominators),
+		(account(), account(), amount()).prop_map(|(a, v, b)| Action::NominatorBondMore(a, v, b)),
+		(account(), account(), amount()).prop_map(|(a, v, b)| Action::NominatorBondLess(a, v, b)),
+		(account(), 0..=100u32).prop_map(|(a, p)| Action::SetAutoCompound(a, p)),
+		(account(), destination()).prop_map(|(a, d)| Action::SetRewardDestination(a, d)),
+	]
+}
+
+fn round_action() -> impl Strategy<Value = Action> {
+	prop_oneof![
+		(0..5usize, 1..=40u32).prop_map(|(i, pts)| Action::Author(i, pts)),
+		(0..=4u32, account()).prop_map(|(r, v)| Action::ClaimRewards(r, v)),
+		(0..=2u32, account()).prop_map(|(r, v)| Action::ReportOffence(r, v)),
+		(1..=10u64).prop_map(Action::Roll),
+	]
+}
+
+fn action() -> impl Strategy<Value = Action> {
+	prop_oneof![candidate_action(), nominator_action(), round_action()]
+}
+
+/// Apply `action`, calls that fail are expected and ignored
+fn apply(action: Action) {
+	let round = Stake::round().current;
+	let _ = match action {
+		Action::JoinCandidates(acc, fee, bond) => Stake::join_candidates(
+			Origin::signed(acc),
+			Perbill::from_percent(fee),
+			bond,
+		),
+		Action::LeaveCandidates(acc) => Stake::leave_candidates(Origin::signed(acc)),
+		Action::GoOffline(acc) => Stake::go_offline(Origin::signed(acc)),
+		Action::GoOnline(acc) => Stake::go_online(Origin::signed(acc)),
+		Action::CandidateBondMore(acc, more) => {
+			Stake::candidate_bond_more(Origin::signed(acc), more)
+		}
+		Action::CandidateBondLess(acc, less) => {
+			Stake::candidate_bond_less(Origin::signed(acc), less)
+		}
+		Action::SetCommission(acc, fee) => {
+			Stake::set_commission(Origin::signed(acc), Perbill::from_percent(fee))
+		}
+		Action::JoinNominators(acc, validator, amount) => {
+			Stake::join_nominators(Origin::signed(acc), validator, amount)
+		}
+		Action::NominateNew(acc, validator, amount) => {
+			Stake::nominate_new(Origin::signed(acc), validator, amount)
+		}
+		Action::SwitchNomination(acc, old, new) => {
+			Stake::switch_nomination(Origin::signed(acc), old, new)
+		}
+		Action::RevokeNomination(acc, validator) => {
+			Stake::revoke_nomination(Origin::signed(acc), validator)
+		}
+		Action::LeaveNominators(acc) => Stake::leave_nominators(Origin::signed(acc)),
+		Action::NominatorBondMore(acc, validator, more) => {
+			Stake::nominator_bond_more(Origin::signed(acc), validator, more)
+		}
+		Action::NominatorBondLess(acc, validator, less) => {
+			Stake::nominator_bond_less(Origin::signed(acc), validator, less)
+		}
+		Action::SetAutoCompound(acc, percent) => {
+			Stake::set_auto_compound(Origin::signed(acc), Perbill::from_percent(percent))
+		}
+		Action::SetRewardDestination(acc, destination) => {
+			Stake::set_reward_destination(Origin::signed(acc), destination)
+		}
+		Action::Author(index, pts) => {
+			let validators = Stake::validators();
+			if !validators.is_empty() {
+				set_author(round, validators[index % validators.len()], pts);
+			}
+			return;
+		}
+		Action::ClaimRewards(ago, validator) => {
+			Stake::claim_rewards(Origin::signed(1), round.saturating_sub(ago), validator)
+		}
+		Action::ReportOffence(ago, validator) => {
+			Stake::report_offence(Origin::root(), validator, round.saturating_sub(ago))
+		}
+		Action::Roll(blocks) => {
+			roll_to(Sys::block_number() + blocks);
+			return;
+		}
+	};
+}
+
+/// Free and reserved balance of all accounts
+fn total_balance() -> Balance {
+	frame_system::Account::<Test>::iter()
+		.map(|(_, info)| info.data.free + info.data.reserved)
+		.sum()
+}
+
+/// Rewards minted and stake slashed according to the events
+fn minted_and_slashed() -> (Balance, Balance) {
+	events()
+		.into_iter()
+		.fold((0, 0), |(minted, slashed), event| match event {
+			Event::Rewarded(_, amount) | Event::ReservedForParachainBond(_, _, amount) => {
+				(minted + amount, slashed)
+			}
+			Event::Slashed(_, amount) => (minted, slashed + amount),
+			_ => (minted, slashed),
+		})
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(64))]
+
+	#[test]
+	fn random_calls_preserve_invariants(actions in prop::collection::vec(action(), 1..60)) {
+		five_validators_five_nominators().execute_with(|| {
+			let initial = total_balance();
+			for action in actions {
+				apply(action);
+				Stake::do_try_state().map_err(TestCaseError::fail)?;
+				let (minted, slashed) = minted_and_slashed();
+				prop_assert_eq!(total_balance(), initial + minted - slashed);
+				prop_assert_eq!(total_balance(), Balances::total_issuance());
+			}
+			Ok(())
+		})?;
+	}
+}
@@ -16,18 +16,18 @@
 
 //! Helper methods for computing issuance based on inflation
 use crate::{BalanceOf, Config};
-use frame_support::traits::{Currency, Get};
+use frame_support::traits::Currency;
 use parity_scale_codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
-use sp_runtime::{Perbill, RuntimeDebug};
+use sp_runtime::{traits::AtLeast32BitUnsigned, Perbill, RuntimeDebug};
 
 const SECONDS_PER_YEAR: u32 = 31557600;
-const SECONDS_PER_BLOCK: u32 = 6;
-const BLOCKS_PER_YEAR: u32 = SECONDS_PER_YEAR / SECONDS_PER_BLOCK;
 
-fn rounds_per_year<T: Config>() -> u32 {
-	BLOCKS_PER_YEAR / T::BlocksPerRound::get()
+/// Number of rounds of `round_length` blocks of `block_time` seconds in a year, at least 1
+pub fn rounds_per_year(round_length: u32, block_time: u32) -> u32 {
+	let blocks_per_year = SECONDS_PER_YEAR / block_time.max(1);
+	(blocks_per_year / round_length.max(1)).max(1)
 }
 
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -54,9 +54,14 @@ impl<T: Ord + Copy> From<T> for Range<T> {
 	}
 }
 
-/// Convert annual inflation rate range to round inflation range
-pub fn annual_to_round<T: Config>(annual: Range<Perbill>) -> Range<Perbill> {
-	let periods = rounds_per_year::<T>();
+/// Convert annual inflation rate range to round inflation range for rounds of `round_length`
+/// blocks of `block_time` seconds
+pub fn annual_to_round(
+	annual: Range<Perbill>,
+	round_length: u32,
+	block_time: u32,
+) -> Range<Perbill> {
+	let periods = rounds_per_year(round_length, block_time);
 	Range {
 		min: Perbill::from_parts(annual.min.deconstruct() / periods),
 		ideal: Perbill::from_parts(annual.ideal.deconstruct() / periods),
@@ -64,6 +69,73 @@ pub fn annual_to_round<T: Config>(annual: Range<Perbill>) -> Range<Perbill> {
 	}
 }
 
+/// Convert round inflation rate range for rounds of `round_length` blocks of `block_time` seconds
+/// to annual inflation rate range, saturating at 100%
+pub fn round_to_annual(
+	round: Range<Perbill>,
+	round_length: u32,
+	block_time: u32,
+) -> Range<Perbill> {
+	let periods = rounds_per_year(round_length, block_time);
+	Range {
+		min: Perbill::from_parts(round.min.deconstruct().saturating_mul(periods)),
+		ideal: Perbill::from_parts(round.ideal.deconstruct().saturating_mul(periods)),
+		max: Perbill::from_parts(round.max.deconstruct().saturating_mul(periods)),
+	}
+}
+
+/// Staking expectations, as amounts staked or as fractions of the total issuance staked
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Eq, PartialEq, Clone, Encode, Decode, RuntimeDebug)]
+pub enum Expectations<Balance> {
+	/// Amounts staked, to be updated by governance as the total issuance grows
+	Absolute(Range<Balance>),
+	/// Fractions of the total issuance staked
+	Relative(Range<Perbill>),
+}
+
+impl<Balance: Default> Default for Expectations<Balance> {
+	fn default() -> Expectations<Balance> {
+		Expectations::Absolute(Range::default())
+	}
+}
+
+impl<Balance> From<Range<Balance>> for Expectations<Balance> {
+	fn from(other: Range<Balance>) -> Expectations<Balance> {
+		Expectations::Absolute(other)
+	}
+}
+
+impl<Balance: Ord> Expectations<Balance> {
+	pub fn is_valid(&self) -> bool {
+		match self {
+			Expectations::Absolute(range) => range.is_valid(),
+			Expectations::Relative(range) => range.is_valid(),
+		}
+	}
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> Expectations<Balance> {
+	/// Expected amounts staked when the total issuance is `total_issuance`
+	pub fn amounts(&self, total_issuance: Balance) -> Range<Balance> {
+		match self {
+			Expectations::Absolute(range) => range.clone(),
+			Expectations::Relative(range) => Range {
+				min: range.min * total_issuance,
+				ideal: range.ideal * total_issuance,
+				max: range.max * total_issuance,
+			},
+		}
+	}
+}
+
+/// Compute expected stake range from staking expectations and current total issuance
+pub fn expected_stake_range<T: Config>(
+	expect: &Expectations<BalanceOf<T>>,
+) -> Range<BalanceOf<T>> {
+	expect.amounts(T::Currency::total_issuance())
+}
+
 /// Compute round issuance range from round inflation range and current total issuance
 pub fn round_issuance_range<T: Config>(round: Range<Perbill>) -> Range<BalanceOf<T>> {
 	let circulating = T::Currency::total_issuance();
@@ -74,31 +146,118 @@ pub fn round_issuance_range<T: Config>(round: Range<Perbill>) -> Range<BalanceOf
 	}
 }
 
+/// Shape of the round issuance as a function of the total staked. Below `expect.min` the issuance
+/// is always `min` and it increases linearly to `ideal` as stake reaches `expect.ideal`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Eq, PartialEq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+pub enum IssuanceCurve {
+	/// Issuance increases linearly from `ideal` to `max` as stake goes from `expect.ideal` to
+	/// `expect.max` and it is `max` above `expect.max`
+	Linear,
+	/// Issuance peaks at `ideal` and decreases linearly back to `min` as stake goes from
+	/// `expect.ideal` to `expect.max`, it is `min` above `expect.max`
+	PeakAtIdeal,
+}
+
+impl Default for IssuanceCurve {
+	fn default() -> IssuanceCurve {
+		IssuanceCurve::Linear
+	}
+}
+
+/// Interpolate the issuance for `staked` on the segment from `(from, from_issuance)` to
+/// `(to, to_issuance)`, `staked` must be in `from..=to`
+fn interpolate<Balance: AtLeast32BitUnsigned + Copy>(
+	staked: Balance,
+	from: Balance,
+	to: Balance,
+	from_issuance: Balance,
+	to_issuance: Balance,
+) -> Balance {
+	if to == from {
+		return to_issuance;
+	}
+	let progress = Perbill::from_rational_approximation(staked - from, to - from);
+	if to_issuance >= from_issuance {
+		from_issuance + progress * (to_issuance - from_issuance)
+	} else {
+		from_issuance - progress * (from_issuance - to_issuance)
+	}
+}
+
+/// Compute the round issuance for `staked` from the staking expectations and the round issuance
+/// range according to the `curve`
+pub fn issuance_for<Balance: AtLeast32BitUnsigned + Copy>(
+	staked: Balance,
+	expect: &Range<Balance>,
+	issuance: &Range<Balance>,
+	curve: IssuanceCurve,
+) -> Balance {
+	if staked < expect.min {
+		return issuance.min;
+	}
+	if staked <= expect.ideal {
+		return interpolate(
+			staked,
+			expect.min,
+			expect.ideal,
+			issuance.min,
+			issuance.ideal,
+		);
+	}
+	let beyond_ideal = match curve {
+		IssuanceCurve::Linear => issuance.max,
+		IssuanceCurve::PeakAtIdeal => issuance.min,
+	};
+	if staked >= expect.max {
+		beyond_ideal
+	} else {
+		interpolate(
+			staked,
+			expect.ideal,
+			expect.max,
+			issuance.ideal,
+			beyond_ideal,
+		)
+	}
+}
+
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Eq, PartialEq, Clone, Encode, Decode, Default, RuntimeDebug)]
 pub struct InflationInfo<Balance> {
 	/// Staking expectations
-	pub expect: Range<Balance>,
-	/// Round inflation range
+	pub expect: Expectations<Balance>,
+	/// Annual inflation range
+	pub annual: Range<Perbill>,
+	/// Round inflation range, derived from `annual` for the length of the current round
 	pub round: Range<Perbill>,
 }
 
 impl<Balance> InflationInfo<Balance> {
-	pub fn new<T: Config>(
+	pub fn new(
 		annual: Range<Perbill>,
-		expect: Range<Balance>,
+		expect: Expectations<Balance>,
+		round_length: u32,
+		block_time: u32,
 	) -> InflationInfo<Balance> {
 		InflationInfo {
 			expect,
-			round: annual_to_round::<T>(annual),
+			annual: annual.clone(),
+			round: annual_to_round(annual, round_length, block_time),
 		}
 	}
-	/// Set round inflation range according to input annual inflation range
-	pub fn set_annual_rate<T: Config>(&mut self, new: Range<Perbill>) {
-		self.round = annual_to_round::<T>(new);
+	/// Set annual inflation range and round inflation range for rounds of `round_length` blocks
+	/// of `block_time` seconds
+	pub fn set_annual_rate(&mut self, new: Range<Perbill>, round_length: u32, block_time: u32) {
+		self.round = annual_to_round(new.clone(), round_length, block_time);
+		self.annual = new;
+	}
+	/// Recompute round inflation range from annual inflation range for a new round length
+	pub fn set_round_length(&mut self, round_length: u32, block_time: u32) {
+		self.round = annual_to_round(self.annual.clone(), round_length, block_time);
 	}
 	/// Set staking expectations
-	pub fn set_expectations(&mut self, expect: Range<Balance>) {
+	pub fn set_expectations(&mut self, expect: Expectations<Balance>) {
 		self.expect = expect;
 	}
 }
@@ -165,6 +324,114 @@ mod tests {
 			mock_round_issuance_range(10_000_000, mock_annual_to_round(schedule, 10))
 		);
 	}
+	fn issuance_at(staked: u128, curve: IssuanceCurve) -> u128 {
+		let expect = Range {
+			min: 100,
+			ideal: 200,
+			max: 400,
+		};
+		let issuance = Range {
+			min: 10,
+			ideal: 20,
+			max: 40,
+		};
+		issuance_for(staked, &expect, &issuance, curve)
+	}
+	#[test]
+	fn linear_issuance_is_interpolated_between_expectations() {
+		// below expectations
+		assert_eq!(issuance_at(0, IssuanceCurve::Linear), 10);
+		assert_eq!(issuance_at(99, IssuanceCurve::Linear), 10);
+		// min -> ideal
+		assert_eq!(issuance_at(100, IssuanceCurve::Linear), 10);
+		assert_eq!(issuance_at(150, IssuanceCurve::Linear), 15);
+		assert_eq!(issuance_at(200, IssuanceCurve::Linear), 20);
+		// ideal -> max
+		assert_eq!(issuance_at(250, IssuanceCurve::Linear), 25);
+		assert_eq!(issuance_at(300, IssuanceCurve::Linear), 30);
+		assert_eq!(issuance_at(400, IssuanceCurve::Linear), 40);
+		// above expectations
+		assert_eq!(issuance_at(1_000, IssuanceCurve::Linear), 40);
+	}
+	#[test]
+	fn peak_issuance_decays_beyond_ideal() {
+		// same as linear up to ideal
+		assert_eq!(issuance_at(99, IssuanceCurve::PeakAtIdeal), 10);
+		assert_eq!(issuance_at(150, IssuanceCurve::PeakAtIdeal), 15);
+		assert_eq!(issuance_at(200, IssuanceCurve::PeakAtIdeal), 20);
+		// ideal -> max decays back to min
+		assert_eq!(issuance_at(300, IssuanceCurve::PeakAtIdeal), 15);
+		assert_eq!(issuance_at(360, IssuanceCurve::PeakAtIdeal), 12);
+		assert_eq!(issuance_at(400, IssuanceCurve::PeakAtIdeal), 10);
+		// above expectations
+		assert_eq!(issuance_at(1_000, IssuanceCurve::PeakAtIdeal), 10);
+	}
+	#[test]
+	fn collapsed_expectations_issue_ideal_at_expectation() {
+		let expect: Range<u128> = 700.into();
+		let issuance = Range {
+			min: 5,
+			ideal: 6,
+			max: 7,
+		};
+		assert_eq!(issuance_for(699, &expect, &issuance, IssuanceCurve::Linear), 5);
+		assert_eq!(issuance_for(700, &expect, &issuance, IssuanceCurve::Linear), 6);
+		assert_eq!(issuance_for(701, &expect, &issuance, IssuanceCurve::Linear), 7);
+		assert_eq!(issuance_for(701, &expect, &issuance, IssuanceCurve::PeakAtIdeal), 5);
+	}
+	#[test]
+	fn relative_expectations_scale_with_total_issuance() {
+		let absolute: Expectations<u128> = Range {
+			min: 100,
+			ideal: 200,
+			max: 500,
+		}
+		.into();
+		assert_eq!(absolute.amounts(10_000), absolute.amounts(1_000_000));
+		let relative: Expectations<u128> = Expectations::Relative(Range {
+			min: Perbill::from_percent(10),
+			ideal: Perbill::from_percent(20),
+			max: Perbill::from_percent(50),
+		});
+		assert_eq!(
+			relative.amounts(1_000),
+			Range {
+				min: 100,
+				ideal: 200,
+				max: 500,
+			}
+		);
+		assert_eq!(
+			relative.amounts(10_000),
+			Range {
+				min: 1_000,
+				ideal: 2_000,
+				max: 5_000,
+			}
+		);
+	}
+	#[test]
+	fn round_inflation_follows_round_length() {
+		let annual = Range {
+			min: Perbill::from_percent(4),
+			ideal: Perbill::from_percent(5),
+			max: Perbill::from_percent(5),
+		};
+		// 8766 rounds of 600 blocks of 6 seconds (an hour) in a year
+		assert_eq!(rounds_per_year(600, 6), 8766);
+		// twice as few rounds of the same length with blocks twice as long
+		assert_eq!(rounds_per_year(600, 12), 4383);
+		let expect = Expectations::Absolute(700.into());
+		let mut config: InflationInfo<u128> = InflationInfo::new(annual.clone(), expect, 600, 6);
+		assert_eq!(config.round, mock_annual_to_round(annual.clone(), 8766));
+		config.set_round_length(1200, 6);
+		assert_eq!(config.annual, annual);
+		assert_eq!(config.round, mock_annual_to_round(annual.clone(), 4383));
+		// converting back is only exact up to rounding
+		let annual_again = round_to_annual(config.round.clone(), 1200, 6);
+		assert!(annual_again.max <= annual.max);
+		assert!(annual.max.deconstruct() - annual_again.max.deconstruct() < 4383);
+	}
 	#[test]
 	fn expected_parameterization() {
 		let expected_round_schedule: Range<u128> = Range {
@@ -18,29 +18,66 @@
 //! Minimal staking pallet that implements ordered validator selection by total amount at stake
 //!
 //! ### Rules
-//! There is a new round every `BlocksPerRound` blocks.
+//! There is a new round every `blocks_per_round` blocks. The index, first block and length of the
+//! current round are recorded in `Round`, so a new round length only applies from the next round
+//! and the round inflation is recomputed from the annual inflation for the new length.
+//!
+//! The round length, the number of validators, the maximum nominators per validator and the
+//! minimum stakes are the `StakingParams` in storage, which `SetMonetaryPolicyOrigin` changes with
+//! `set_staking_params`. The change applies at the start of the next round, within the bounds set
+//! by the runtime (`MaxValidators`, `MaxNominatorsPerValidator` and `MinNomination`).
 //!
 //! At the start of every round,
-//! * `IssuancePerRound` is distributed to validators for `BondDuration` rounds ago
-//! in proportion to the points they received in that round (for authoring blocks)
+//! * the validators of the ending round receive points according to their `Uptime`, the fraction
+//! of the blocks for which the author filter made them eligible that they authored
+//! * validators that authored none of the blocks they were eligible for in `MaxMissedRounds`
+//! consecutive rounds are made idle, as with `go_offline`
+//! * the `ParachainBondInfo` percent of the issuance of the round `BondDuration` rounds ago is
+//! minted to the parachain bond account
+//! * the rest of that issuance becomes claimable by the validators of the round in proportion
+//! to the points they received in that round
+//! * unclaimed rewards of the round `RewardClaimWindow` rounds before that expire
 //! * queued validator exits are executed
 //! * a new set of validators is chosen from the candidates
 //!
 //! To join the set of candidates, an account must call `join_candidates` with
-//! stake >= `MinValidatorStk` and fee <= `MaxFee`. The fee is taken off the top
+//! stake >= `min_validator_stk` and fee <= `MaxFee`. The fee is taken off the top
 //! of any rewards for the validator before the remaining rewards are distributed
 //! in proportion to stake to all nominators (including the validator, who always
-//! self-nominates).
+//! self-nominates). The fee is changed with `set_commission`, which only applies
+//! `CommissionChangeDelay` rounds later so that nominators are warned of any increase.
+//!
+//! A candidate account holds the bond and acts as its stash. With `set_controller` the stash may
+//! designate a controller that signs the operational calls on its behalf, and with
+//! `set_author_key` a separate key that the node claims blocks with in `author_inherent`. The
+//! author filter and the points of authored blocks resolve that key back to the stash.
+//!
+//! Candidates may publish a display name, website, contact and free-form JSON for nominators
+//! with `set_metadata`, which reserves `MetadataDeposit` until `clear_metadata` or their exit.
+//!
+//! Rewards are paid out when any account calls `claim_rewards` for a validator and round, which
+//! pays the validator and all of its nominators in the `AtStake` snapshot of the round.
+//! Validators and nominators may call `set_auto_compound` to have a fraction of their rewards
+//! bonded back to the validator that earned them, and `set_reward_destination` to have their
+//! rewards paid to another account or entirely bonded back.
 //!
 //! To leave the set of candidates, the validator calls `leave_candidates`. If the call succeeds,
 //! the validator is removed from the pool of candidates so they cannot be selected for future
 //! validator sets, but they are not unstaked until `BondDuration` rounds later. The exit request is
 //! stored in the `ExitQueue` and processed `BondDuration` rounds later to unstake the validator
-//! and all of its nominators.
+//! and unbond all of its nominations.
 //!
 //! To join the set of nominators, an account must call `join_nominators` with
-//! stake >= `MinNominatorStk`. There are also runtime methods for nominating additional validators
-//! and revoking nominations.
+//! stake >= `min_nominator_stk`. A validator accepts at most `max_nominators_per_validator`
+//! nominations, after which a new nomination must be larger than the lowest one, which is evicted
+//! and unbonded. There are also runtime methods for nominating additional validators and revoking
+//! nominations. Stake removed by nominators is queued in `Unbonding` and only becomes liquid
+//! `UnbondingDelay` rounds later, so it remains slashable in the meantime.
+//!
+//! Offences are reported by `ReportOrigin` through `report_offence`. The slash takes
+//! `SlashFraction` of the validator's bond and of every nomination recorded for it in `AtStake` for
+//! the offence round. Slashes are applied `SlashDeferDuration` rounds after being reported, during
+//! which time `SlashCancelOrigin` may cancel them.
 
 #![recursion_limit = "256"]
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -48,456 +85,772 @@
 // https://github.com/PureStake/moonbeam/pull/189
 #![allow(clippy::all)]
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod fuzz;
 mod inflation;
-pub use inflation::{InflationInfo, Range};
+pub use inflation::{Expectations, InflationInfo, IssuanceCurve, Range};
+pub mod migrations;
 #[cfg(test)]
 pub(crate) mod mock;
 mod set;
 #[cfg(test)]
 mod tests;
-use frame_support::{
-	decl_error, decl_event, decl_module, decl_storage, ensure,
-	storage::IterableStorageDoubleMap,
-	traits::{Currency, EnsureOrigin, Get, Imbalance, ReservableCurrency},
-};
-use frame_system::{ensure_signed, Config as System};
-use parity_scale_codec::{Decode, Encode};
-use set::OrderedSet;
-use sp_runtime::{
-	traits::{AtLeast32BitUnsigned, Zero},
-	DispatchResult, Perbill, RuntimeDebug,
-};
-use sp_std::{cmp::Ordering, prelude::*};
-
-#[derive(Default, Clone, Encode, Decode, RuntimeDebug)]
-pub struct Bond<AccountId, Balance> {
-	pub owner: AccountId,
-	pub amount: Balance,
-}
+pub mod weights;
+use frame_support::pallet;
+pub use weights::WeightInfo;
 
-impl<A, B: Default> Bond<A, B> {
-	fn from_owner(owner: A) -> Self {
-		Bond {
-			owner,
-			amount: B::default(),
-		}
+pub use pallet::*;
+
+#[pallet]
+pub mod pallet {
+	use crate::{set::OrderedSet, Expectations, InflationInfo, IssuanceCurve, Range, WeightInfo};
+	use frame_support::pallet_prelude::*;
+	use frame_support::traits::{Currency, Imbalance, OnUnbalanced, ReservableCurrency};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::{
+		traits::{AtLeast32BitUnsigned, Saturating, UniqueSaturatedInto, Zero},
+		DispatchError, DispatchResult, Perbill, Percent,
+	};
+	use sp_std::{cmp::Ordering, collections::btree_map::BTreeMap, prelude::*};
+	#[cfg(feature = "std")]
+	use serde::{Deserialize, Serialize};
+
+	/// Pallet for validator selection, staking rewards and slashing
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[derive(Copy, Clone, Default, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+	/// The current round index and the blocks it spans
+	pub struct RoundInfo<BlockNumber> {
+		/// Current round index
+		pub current: RoundIndex,
+		/// First block of the current round
+		pub first_block: BlockNumber,
+		/// Length of the current round in blocks
+		pub length: u32,
 	}
-}
 
-impl<AccountId: Ord, Balance> Eq for Bond<AccountId, Balance> {}
+	impl<B: AtLeast32BitUnsigned + Copy> RoundInfo<B> {
+		pub fn new(current: RoundIndex, first_block: B, length: u32) -> RoundInfo<B> {
+			RoundInfo {
+				current,
+				first_block,
+				length,
+			}
+		}
+		/// Last block of the round, the next round starts in its `on_finalize`
+		pub fn last_block(&self) -> B {
+			self.first_block
+				.saturating_add(self.length.into())
+				.saturating_sub(1u32.into())
+		}
+		/// Returns true if the next round starts in `on_finalize` of block `now`
+		pub fn should_update(&self, now: B) -> bool {
+			now >= self.last_block()
+		}
+		/// Number of blocks after `now` until the round changes, including the block changing it
+		pub fn blocks_left(&self, now: B) -> u32 {
+			self.last_block().saturating_sub(now).unique_saturated_into()
+		}
+	}
 
-impl<AccountId: Ord, Balance> Ord for Bond<AccountId, Balance> {
-	fn cmp(&self, other: &Self) -> Ordering {
-		self.owner.cmp(&other.owner)
+	#[derive(Copy, Clone, Default, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+	/// Slots of a round for which a validator was eligible to author and blocks it authored
+	pub struct UptimeInfo {
+		/// Number of blocks for which the validator was selected by the author filter
+		pub eligible: u32,
+		/// Number of blocks authored by the validator
+		pub authored: u32,
 	}
-}
 
-impl<AccountId: Ord, Balance> PartialOrd for Bond<AccountId, Balance> {
-	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-		Some(self.cmp(other))
+	impl UptimeInfo {
+		/// Fraction of the slots for which the validator was eligible that it authored. All
+		/// authored blocks count as eligible slots when the eligibility is not reported.
+		pub fn fraction(&self) -> Perbill {
+			Perbill::from_rational_approximation(self.authored, self.eligible.max(self.authored))
+		}
+		/// Points awarded for the round, `UPTIME_POINTS` scaled by the uptime fraction
+		pub fn points(&self) -> RewardPoint {
+			self.fraction() * UPTIME_POINTS
+		}
 	}
-}
 
-impl<AccountId: Ord, Balance> PartialEq for Bond<AccountId, Balance> {
-	fn eq(&self, other: &Self) -> bool {
-		self.owner == other.owner
+	#[derive(Default, Clone, Encode, Decode, RuntimeDebug)]
+	pub struct Bond<AccountId, Balance> {
+		pub owner: AccountId,
+		pub amount: Balance,
 	}
-}
 
-#[derive(Copy, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
-/// The activity status of the validator
-pub enum ValidatorStatus {
-	/// Committed to be online and producing valid blocks (not equivocating)
-	Active,
-	/// Temporarily inactive and excused for inactivity
-	Idle,
-	/// Bonded until the inner round
-	Leaving(RoundIndex),
-}
+	impl<AccountId: Ord, Balance> Eq for Bond<AccountId, Balance> {}
 
-impl Default for ValidatorStatus {
-	fn default() -> ValidatorStatus {
-		ValidatorStatus::Active
+	impl<AccountId: Ord, Balance> Ord for Bond<AccountId, Balance> {
+		fn cmp(&self, other: &Self) -> Ordering {
+			self.owner.cmp(&other.owner)
+		}
 	}
-}
 
-#[derive(Default, Encode, Decode, RuntimeDebug)]
-/// Snapshot of validator state at the start of the round for which they are selected
-pub struct ValidatorSnapshot<AccountId, Balance> {
-	pub fee: Perbill,
-	pub bond: Balance,
-	pub nominators: Vec<Bond<AccountId, Balance>>,
-	pub total: Balance,
-}
+	impl<AccountId: Ord, Balance> PartialOrd for Bond<AccountId, Balance> {
+		fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+			Some(self.cmp(other))
+		}
+	}
 
-#[derive(Encode, Decode, RuntimeDebug)]
-/// Global validator state with commission fee, bonded stake, and nominations
-pub struct Validator<AccountId, Balance> {
-	pub id: AccountId,
-	pub fee: Perbill,
-	pub bond: Balance,
-	pub nominators: OrderedSet<Bond<AccountId, Balance>>,
-	pub total: Balance,
-	pub state: ValidatorStatus,
-}
+	impl<AccountId: Ord, Balance> PartialEq for Bond<AccountId, Balance> {
+		fn eq(&self, other: &Self) -> bool {
+			self.owner == other.owner
+		}
+	}
 
-impl<
-		A: Ord + Clone,
-		B: AtLeast32BitUnsigned + Ord + Copy + sp_std::ops::AddAssign + sp_std::ops::SubAssign,
-	> Validator<A, B>
-{
-	pub fn new(id: A, fee: Perbill, bond: B) -> Self {
-		let total = bond;
-		Validator {
-			id,
-			fee,
-			bond,
-			nominators: OrderedSet::new(),
-			total,
-			state: ValidatorStatus::default(), // default active
+	#[derive(Copy, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+	/// The activity status of the validator
+	pub enum ValidatorStatus {
+		/// Committed to be online and producing valid blocks (not equivocating)
+		Active,
+		/// Temporarily inactive and excused for inactivity
+		Idle,
+		/// Bonded until the inner round
+		Leaving(RoundIndex),
+	}
+
+	impl Default for ValidatorStatus {
+		fn default() -> ValidatorStatus {
+			ValidatorStatus::Active
 		}
 	}
-	pub fn is_active(&self) -> bool {
-		self.state == ValidatorStatus::Active
+
+	#[derive(Default, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+	/// Reserve account that receives a cut of the issuance of every round before stakers are paid
+	pub struct ParachainBondConfig<AccountId> {
+		/// Account that receives the cut
+		pub account: AccountId,
+		/// Percent of the round issuance that is minted to the account
+		pub percent: Percent,
 	}
-	pub fn is_leaving(&self) -> bool {
-		matches!(self.state, ValidatorStatus::Leaving(_))
+
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	#[derive(Default, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+	/// Staking parameters set by `SetMonetaryPolicyOrigin`, see `set_staking_params`
+	pub struct StakingParams<Balance> {
+		/// Number of blocks per round
+		pub blocks_per_round: u32,
+		/// Maximum validators per round, at most `MaxValidators`
+		pub max_validators: u32,
+		/// Maximum nominators per validator, at most `MaxNominatorsPerValidator`
+		pub max_nominators_per_validator: u32,
+		/// Minimum stake for any registered on-chain account to become a validator, at least
+		/// `min_nominator_stk`
+		pub min_validator_stk: Balance,
+		/// Minimum stake for any registered on-chain account to become a nominator, at least
+		/// `MinNomination`
+		pub min_nominator_stk: Balance,
 	}
-	pub fn bond_more(&mut self, more: B) {
-		self.bond += more;
-		self.total += more;
+
+	#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+	/// Where the rewards of a validator candidate or nominator are paid
+	pub enum RewardDestination<AccountId> {
+		/// Into the free balance of the staking account, bonding back its `AutoCompound` fraction
+		Staker,
+		/// Into the free balance of another account, except for the `AutoCompound` fraction which
+		/// is paid to the staking account and bonded back
+		Account(AccountId),
+		/// Into the staking account, all of it bonded back regardless of `AutoCompound`
+		Restake,
 	}
-	// Returns None if underflow or less == self.bond (in which case validator should leave instead)
-	pub fn bond_less(&mut self, less: B) -> Option<B> {
-		if self.bond > less {
-			self.bond -= less;
-			self.total -= less;
-			Some(self.bond)
-		} else {
-			None
+
+	impl<AccountId> Default for RewardDestination<AccountId> {
+		fn default() -> RewardDestination<AccountId> {
+			RewardDestination::Staker
 		}
 	}
-	// infallible so nominator must exist before calling
-	pub fn rm_nominator(&mut self, nominator: A) -> B {
-		let mut total = self.total;
-		let nominators = self
-			.nominators
-			.0
-			.iter()
-			.filter_map(|x| {
-				if x.owner == nominator {
-					total -= x.amount;
-					None
-				} else {
-					Some(x.clone())
-				}
-			})
-			.collect();
-		self.nominators = OrderedSet::from(nominators);
-		self.total = total;
-		total
+
+	#[derive(Default, Encode, Decode, RuntimeDebug)]
+	/// Snapshot of validator state at the start of the round for which they are selected
+	pub struct ValidatorSnapshot<AccountId, Balance> {
+		pub fee: Perbill,
+		pub bond: Balance,
+		pub nominators: Vec<Bond<AccountId, Balance>>,
+		pub total: Balance,
 	}
-	// infallible so nominator dne before calling
-	pub fn add_nominator(&mut self, owner: A, amount: B) -> B {
-		self.nominators.insert(Bond { owner, amount });
-		self.total += amount;
-		self.total
+
+	#[derive(Clone, Default, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+	/// Information published by a validator candidate for nominators, bounded by
+	/// `MaxMetadataFieldLength` and `MaxMetadataExtraLength`
+	pub struct CandidateMetadata<Balance> {
+		/// Name under which the candidate is displayed
+		pub display_name: Vec<u8>,
+		/// Website of the operator of the candidate
+		pub website: Vec<u8>,
+		/// How to contact the operator of the candidate
+		pub contact: Vec<u8>,
+		/// Free-form JSON
+		pub extra: Vec<u8>,
+		/// Amount reserved from the candidate for as long as the metadata is stored
+		pub deposit: Balance,
 	}
-	// only call with an amount larger than existing amount
-	pub fn update_nominator(&mut self, nominator: A, amount: B) -> B {
-		let mut difference: B = 0u32.into();
-		let nominators = self
-			.nominators
-			.0
-			.iter()
-			.map(|x| {
-				if x.owner == nominator {
-					// new amount must be greater or will underflow
-					difference = amount - x.amount;
-					Bond {
-						owner: x.owner.clone(),
-						amount,
-					}
-				} else {
-					x.clone()
-				}
-			})
-			.collect();
-		self.nominators = OrderedSet::from(nominators);
-		self.total += difference;
-		self.total
+
+	#[derive(Encode, Decode, RuntimeDebug)]
+	/// Global validator state with commission fee, bonded stake, and nominations
+	pub struct Validator<AccountId, Balance> {
+		pub id: AccountId,
+		pub fee: Perbill,
+		pub bond: Balance,
+		pub nominators: OrderedSet<Bond<AccountId, Balance>>,
+		pub total: Balance,
+		pub state: ValidatorStatus,
+		/// Round in which the account joined the set of candidates, 0 if it joined at genesis or
+		/// before this was recorded
+		pub joined: RoundIndex,
 	}
-	pub fn inc_nominator(&mut self, nominator: A, more: B) {
-		for x in &mut self.nominators.0 {
-			if x.owner == nominator {
-				x.amount += more;
-				self.total += more;
-				return;
+
+	impl<
+			A: Ord + Clone,
+			B: AtLeast32BitUnsigned + Ord + Copy + sp_std::ops::AddAssign + sp_std::ops::SubAssign,
+		> Validator<A, B>
+	{
+		pub fn new(id: A, fee: Perbill, bond: B, joined: RoundIndex) -> Self {
+			let total = bond;
+			Validator {
+				id,
+				fee,
+				bond,
+				nominators: OrderedSet::new(),
+				total,
+				state: ValidatorStatus::default(), // default active
+				joined,
 			}
 		}
-	}
-	pub fn dec_nominator(&mut self, nominator: A, less: B) {
-		for x in &mut self.nominators.0 {
-			if x.owner == nominator {
-				x.amount -= less;
+		pub fn is_active(&self) -> bool {
+			self.state == ValidatorStatus::Active
+		}
+		pub fn is_leaving(&self) -> bool {
+			matches!(self.state, ValidatorStatus::Leaving(_))
+		}
+		pub fn bond_more(&mut self, more: B) {
+			self.bond += more;
+			self.total += more;
+		}
+		// Returns None if underflow or less == self.bond (in which case validator should leave
+		// instead)
+		pub fn bond_less(&mut self, less: B) -> Option<B> {
+			if self.bond > less {
+				self.bond -= less;
 				self.total -= less;
-				return;
+				Some(self.bond)
+			} else {
+				None
 			}
 		}
+		// infallible so nominator must exist before calling
+		pub fn rm_nominator(&mut self, nominator: A) -> B {
+			let mut total = self.total;
+			let nominators = self
+				.nominators
+				.0
+				.iter()
+				.filter_map(|x| {
+					if x.owner == nominator {
+						total -= x.amount;
+						None
+					} else {
+						Some(x.clone())
+					}
+				})
+				.collect();
+			self.nominators = OrderedSet::from(nominators);
+			self.total = total;
+			total
+		}
+		// the nomination of the smallest account if several are equally small
+		pub fn lowest_nomination(&self) -> Option<&Bond<A, B>> {
+			self.nominators.0.iter().min_by_key(|x| x.amount)
+		}
+		// infallible so nominator dne before calling
+		pub fn add_nominator(&mut self, owner: A, amount: B) -> B {
+			self.nominators.insert(Bond { owner, amount });
+			self.total += amount;
+			self.total
+		}
+		// only call with an amount larger than existing amount
+		pub fn update_nominator(&mut self, nominator: A, amount: B) -> B {
+			let mut difference: B = 0u32.into();
+			let nominators = self
+				.nominators
+				.0
+				.iter()
+				.map(|x| {
+					if x.owner == nominator {
+						// new amount must be greater or will underflow
+						difference = amount - x.amount;
+						Bond {
+							owner: x.owner.clone(),
+							amount,
+						}
+					} else {
+						x.clone()
+					}
+				})
+				.collect();
+			self.nominators = OrderedSet::from(nominators);
+			self.total += difference;
+			self.total
+		}
+		pub fn inc_nominator(&mut self, nominator: A, more: B) {
+			for x in &mut self.nominators.0 {
+				if x.owner == nominator {
+					x.amount += more;
+					self.total += more;
+					return;
+				}
+			}
+		}
+		pub fn dec_nominator(&mut self, nominator: A, less: B) {
+			for x in &mut self.nominators.0 {
+				if x.owner == nominator {
+					x.amount -= less;
+					self.total -= less;
+					return;
+				}
+			}
+		}
+		// Slash at most `amount` off the bond, returns the amount slashed
+		pub fn slash_bond(&mut self, amount: B) -> B {
+			let slashed = amount.min(self.bond);
+			self.bond -= slashed;
+			self.total -= slashed;
+			slashed
+		}
+		// Slash at most `amount` off the nomination, returns the amount slashed
+		pub fn slash_nominator(&mut self, nominator: A, amount: B) -> B {
+			for x in &mut self.nominators.0 {
+				if x.owner == nominator {
+					let slashed = amount.min(x.amount);
+					x.amount -= slashed;
+					self.total -= slashed;
+					return slashed;
+				}
+			}
+			B::zero()
+		}
+		pub fn go_offline(&mut self) {
+			self.state = ValidatorStatus::Idle;
+		}
+		pub fn go_online(&mut self) {
+			self.state = ValidatorStatus::Active;
+		}
+		pub fn leave_candidates(&mut self, round: RoundIndex) {
+			self.state = ValidatorStatus::Leaving(round);
+		}
 	}
-	pub fn go_offline(&mut self) {
-		self.state = ValidatorStatus::Idle;
-	}
-	pub fn go_online(&mut self) {
-		self.state = ValidatorStatus::Active;
-	}
-	pub fn leave_candidates(&mut self, round: RoundIndex) {
-		self.state = ValidatorStatus::Leaving(round);
-	}
-}
 
-impl<A: Clone, B: Copy> From<Validator<A, B>> for ValidatorSnapshot<A, B> {
-	fn from(other: Validator<A, B>) -> ValidatorSnapshot<A, B> {
-		ValidatorSnapshot {
-			fee: other.fee,
-			bond: other.bond,
-			nominators: other.nominators.0,
-			total: other.total,
+	impl<A: Clone, B: Copy> From<Validator<A, B>> for ValidatorSnapshot<A, B> {
+		fn from(other: Validator<A, B>) -> ValidatorSnapshot<A, B> {
+			ValidatorSnapshot {
+				fee: other.fee,
+				bond: other.bond,
+				nominators: other.nominators.0,
+				total: other.total,
+			}
 		}
 	}
-}
 
-#[derive(Encode, Decode, RuntimeDebug)]
-pub struct Nominator<AccountId, Balance> {
-	pub nominations: OrderedSet<Bond<AccountId, Balance>>,
-	pub total: Balance,
-}
+	#[derive(Encode, Decode, RuntimeDebug)]
+	pub struct Nominator<AccountId, Balance> {
+		pub nominations: OrderedSet<Bond<AccountId, Balance>>,
+		pub total: Balance,
+	}
 
-impl<
-		AccountId: Ord + Clone,
-		Balance: Copy
-			+ sp_std::ops::AddAssign
-			+ sp_std::ops::Add<Output = Balance>
-			+ sp_std::ops::SubAssign
-			+ PartialOrd,
-	> Nominator<AccountId, Balance>
-{
-	pub fn new(validator: AccountId, nomination: Balance) -> Self {
-		Nominator {
-			nominations: OrderedSet::from(vec![Bond {
-				owner: validator,
-				amount: nomination,
-			}]),
-			total: nomination,
+	impl<
+			AccountId: Ord + Clone,
+			Balance: Copy
+				+ sp_std::ops::AddAssign
+				+ sp_std::ops::Add<Output = Balance>
+				+ sp_std::ops::SubAssign
+				+ PartialOrd,
+		> Nominator<AccountId, Balance>
+	{
+		pub fn new(validator: AccountId, nomination: Balance) -> Self {
+			Nominator {
+				nominations: OrderedSet::from(vec![Bond {
+					owner: validator,
+					amount: nomination,
+				}]),
+				total: nomination,
+			}
 		}
-	}
-	pub fn add_nomination(&mut self, bond: Bond<AccountId, Balance>) -> bool {
-		let amt = bond.amount;
-		if self.nominations.insert(bond) {
-			self.total += amt;
-			true
-		} else {
-			false
+		pub fn add_nomination(&mut self, bond: Bond<AccountId, Balance>) -> bool {
+			let amt = bond.amount;
+			if self.nominations.insert(bond) {
+				self.total += amt;
+				true
+			} else {
+				false
+			}
 		}
-	}
-	// Returns Some(remaining balance), must be more than MinNominatorStk
-	// Returns None if nomination not found
-	pub fn rm_nomination(&mut self, validator: AccountId) -> Option<Balance> {
-		let mut amt: Option<Balance> = None;
-		let nominations = self
-			.nominations
-			.0
-			.iter()
-			.filter_map(|x| {
-				if x.owner == validator {
-					amt = Some(x.amount);
-					None
-				} else {
-					Some(x.clone())
-				}
-			})
-			.collect();
-		if let Some(balance) = amt {
-			self.nominations = OrderedSet::from(nominations);
-			self.total -= balance;
-			Some(self.total)
-		} else {
-			None
+		// Returns Some(remaining balance), must be more than min_nominator_stk
+		// Returns None if nomination not found
+		pub fn rm_nomination(&mut self, validator: AccountId) -> Option<Balance> {
+			let mut amt: Option<Balance> = None;
+			let nominations = self
+				.nominations
+				.0
+				.iter()
+				.filter_map(|x| {
+					if x.owner == validator {
+						amt = Some(x.amount);
+						None
+					} else {
+						Some(x.clone())
+					}
+				})
+				.collect();
+			if let Some(balance) = amt {
+				self.nominations = OrderedSet::from(nominations);
+				self.total -= balance;
+				Some(self.total)
+			} else {
+				None
+			}
 		}
-	}
-	// Returns Some(new balances) if old was nominated and None if it wasn't nominated
-	pub fn swap_nomination(
-		&mut self,
-		old: AccountId,
-		new: AccountId,
-	) -> Option<(Balance, Balance)> {
-		let mut amt: Option<Balance> = None;
-		let nominations = self
-			.nominations
-			.0
-			.iter()
-			.filter_map(|x| {
-				if x.owner == old {
-					amt = Some(x.amount);
-					None
-				} else {
-					Some(x.clone())
-				}
-			})
-			.collect();
-		if let Some(swapped_amt) = amt {
-			let mut old_new_amt: Option<Balance> = None;
-			let nominations2 = self
+		// Returns Some(new balances) if old was nominated and None if it wasn't nominated
+		pub fn swap_nomination(
+			&mut self,
+			old: AccountId,
+			new: AccountId,
+		) -> Option<(Balance, Balance)> {
+			let mut amt: Option<Balance> = None;
+			let nominations: Vec<Bond<AccountId, Balance>> = self
 				.nominations
 				.0
 				.iter()
 				.filter_map(|x| {
-					if x.owner == new {
-						old_new_amt = Some(x.amount);
+					if x.owner == old {
+						amt = Some(x.amount);
 						None
 					} else {
 						Some(x.clone())
 					}
 				})
 				.collect();
-			let new_amount = if let Some(old_amt) = old_new_amt {
-				// update existing nomination
-				self.nominations = OrderedSet::from(nominations2);
-				let new_amt = old_amt + swapped_amt;
-				self.nominations.insert(Bond {
-					owner: new,
-					amount: new_amt,
-				});
-				new_amt
+			if let Some(swapped_amt) = amt {
+				let mut old_new_amt: Option<Balance> = None;
+				let nominations2 = nominations
+					.iter()
+					.filter_map(|x| {
+						if x.owner == new {
+							old_new_amt = Some(x.amount);
+							None
+						} else {
+							Some(x.clone())
+						}
+					})
+					.collect();
+				let new_amount = if let Some(old_amt) = old_new_amt {
+					// update existing nomination
+					self.nominations = OrderedSet::from(nominations2);
+					let new_amt = old_amt + swapped_amt;
+					self.nominations.insert(Bond {
+						owner: new,
+						amount: new_amt,
+					});
+					new_amt
+				} else {
+					// insert completely new nomination
+					self.nominations = OrderedSet::from(nominations);
+					self.nominations.insert(Bond {
+						owner: new,
+						amount: swapped_amt,
+					});
+					swapped_amt
+				};
+				Some((swapped_amt, new_amount))
 			} else {
-				// insert completely new nomination
-				self.nominations = OrderedSet::from(nominations);
-				self.nominations.insert(Bond {
-					owner: new,
-					amount: swapped_amt,
-				});
-				swapped_amt
-			};
-			Some((swapped_amt, new_amount))
-		} else {
+				None
+			}
+		}
+		// Returns None if nomination not found
+		pub fn inc_nomination(&mut self, validator: AccountId, more: Balance) -> Option<Balance> {
+			for x in &mut self.nominations.0 {
+				if x.owner == validator {
+					x.amount += more;
+					self.total += more;
+					return Some(x.amount);
+				}
+			}
 			None
 		}
-	}
-	// Returns None if nomination not found
-	pub fn inc_nomination(&mut self, validator: AccountId, more: Balance) -> Option<Balance> {
-		for x in &mut self.nominations.0 {
-			if x.owner == validator {
-				x.amount += more;
-				self.total += more;
-				return Some(x.amount);
+		// Returns Some(Some(balance)) if successful
+		// None if nomination not found
+		// Some(None) if underflow
+		pub fn dec_nomination(
+			&mut self,
+			validator: AccountId,
+			less: Balance,
+		) -> Option<Option<Balance>> {
+			for x in &mut self.nominations.0 {
+				if x.owner == validator {
+					if x.amount > less {
+						x.amount -= less;
+						self.total -= less;
+						return Some(Some(x.amount));
+					} else {
+						// underflow error; should rm entire nomination if x.amount == validator
+						return Some(None);
+					}
+				}
 			}
+			None
 		}
-		None
-	}
-	// Returns Some(Some(balance)) if successful
-	// None if nomination not found
-	// Some(None) if underflow
-	pub fn dec_nomination(
-		&mut self,
-		validator: AccountId,
-		less: Balance,
-	) -> Option<Option<Balance>> {
-		for x in &mut self.nominations.0 {
-			if x.owner == validator {
-				if x.amount > less {
-					x.amount -= less;
-					self.total -= less;
-					return Some(Some(x.amount));
-				} else {
-					// underflow error; should rm entire nomination if x.amount == validator
-					return Some(None);
+		// Slash at most `amount` off the nomination, returns the amount slashed
+		// Returns None if nomination not found
+		pub fn slash_nomination(
+			&mut self,
+			validator: AccountId,
+			amount: Balance,
+		) -> Option<Balance> {
+			for x in &mut self.nominations.0 {
+				if x.owner == validator {
+					let slashed = if amount < x.amount { amount } else { x.amount };
+					x.amount -= slashed;
+					self.total -= slashed;
+					return Some(slashed);
 				}
 			}
+			None
 		}
-		None
 	}
-}
 
-type RoundIndex = u32;
-type RewardPoint = u32;
-type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as System>::AccountId>>::Balance;
-type Candidate<T> = Validator<<T as System>::AccountId, BalanceOf<T>>;
-
-pub trait Config: System {
-	/// The overarching event type
-	type Event: From<Event<Self>> + Into<<Self as System>::Event>;
-	/// The currency type
-	type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
-	/// The origin for setting inflation
-	type SetMonetaryPolicyOrigin: EnsureOrigin<Self::Origin>;
-	/// Number of blocks per round
-	type BlocksPerRound: Get<u32>;
-	/// Number of rounds that validators remain bonded before exit request is executed
-	type BondDuration: Get<RoundIndex>;
-	/// Maximum validators per round
-	type MaxValidators: Get<u32>;
-	/// Maximum nominators per validator
-	type MaxNominatorsPerValidator: Get<u32>;
-	/// Maximum validators per nominator
-	type MaxValidatorsPerNominator: Get<u32>;
-	/// Maximum fee for any validator
-	type MaxFee: Get<Perbill>;
-	/// Minimum stake for any registered on-chain account to become a validator
-	type MinValidatorStk: Get<BalanceOf<Self>>;
-	/// Minimum stake for any registered on-chain account to nominate
-	type MinNomination: Get<BalanceOf<Self>>;
-	/// Minimum stake for any registered on-chain account to become a nominator
-	type MinNominatorStk: Get<BalanceOf<Self>>;
-}
+	#[derive(Clone, PartialEq, Encode, Decode, RuntimeDebug)]
+	/// Nominated stake that is waiting to be unreserved
+	pub struct UnbondingChunk<AccountId, Balance> {
+		/// The validator that the stake was nominated to
+		pub validator: AccountId,
+		pub amount: Balance,
+		/// The round at which the stake becomes liquid
+		pub when: RoundIndex,
+	}
 
-decl_event!(
-	pub enum Event<T>
-	where
-		AccountId = <T as System>::AccountId,
-		Balance = BalanceOf<T>,
-		BlockNumber = <T as System>::BlockNumber,
-	{
+	#[derive(Clone, PartialEq, Encode, Decode, RuntimeDebug)]
+	/// A slash computed from an offence report, waiting to be applied
+	pub struct UnappliedSlash<AccountId, Balance> {
+		/// The offending validator
+		pub validator: AccountId,
+		/// Amount slashed from the validator's bond
+		pub own: Balance,
+		/// Amounts slashed from the nominations of the validator
+		pub others: Vec<Bond<AccountId, Balance>>,
+	}
+
+	#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, RuntimeDebug)]
+	/// Version of the layout of the pallet storage, see `migrations.rs`
+	pub enum Releases {
+		/// Layout before storage versioning was introduced
+		V1_0_0,
+		/// `Validator` records the round in which it joined the candidates
+		V2_0_0,
+		/// `Round` records the first block and length of the round, `InflationInfo` records the
+		/// annual inflation and `Params` holds the staking parameters
+		V3_0_0,
+		/// `InflationInfo` records the staking expectations as amounts or as fractions of the
+		/// total issuance
+		V4_0_0,
+	}
+
+	impl Default for Releases {
+		fn default() -> Releases {
+			Releases::V1_0_0
+		}
+	}
+
+	pub type RoundIndex = u32;
+	pub type RewardPoint = u32;
+	/// Points awarded to a validator that authored every block for which it was eligible
+	pub const UPTIME_POINTS: RewardPoint = 1_000;
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+		<T as frame_system::Config>::AccountId,
+	>>::NegativeImbalance;
+	type Candidate<T> = Validator<<T as frame_system::Config>::AccountId, BalanceOf<T>>;
+
+	/// Configuration trait of this pallet
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The currency type
+		type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
+		/// The origin for setting inflation
+		type SetMonetaryPolicyOrigin: EnsureOrigin<Self::Origin>;
+		/// Expected number of seconds between blocks, used to convert the annual inflation to the
+		/// inflation of a round
+		#[pallet::constant]
+		type BlockTime: Get<u32>;
+		/// Number of rounds that validators remain bonded before exit request is executed
+		#[pallet::constant]
+		type BondDuration: Get<RoundIndex>;
+		/// Number of rounds that the rewards of a round can be claimed for before they expire
+		#[pallet::constant]
+		type RewardClaimWindow: Get<RoundIndex>;
+		/// Number of rounds that the history of a round (snapshots, points and offenders) is kept
+		/// for, at least `BondDuration + RewardClaimWindow`
+		#[pallet::constant]
+		type HistoryDepth: Get<RoundIndex>;
+		/// Maximum number of history entries pruned in `on_initialize` of every block
+		#[pallet::constant]
+		type MaxPrunedPerBlock: Get<u32>;
+		/// Number of rounds that stake removed by nominators remains bonded before it is unreserved
+		#[pallet::constant]
+		type UnbondingDelay: Get<RoundIndex>;
+		/// Upper bound on `max_validators` in `StakingParams`, used to weigh round transitions
+		#[pallet::constant]
+		type MaxValidators: Get<u32>;
+		/// Upper bound on `max_nominators_per_validator` in `StakingParams`, used to weigh calls
+		#[pallet::constant]
+		type MaxNominatorsPerValidator: Get<u32>;
+		/// Maximum validators per nominator
+		#[pallet::constant]
+		type MaxValidatorsPerNominator: Get<u32>;
+		/// Maximum fee for any validator
+		#[pallet::constant]
+		type MaxFee: Get<Perbill>;
+		/// Number of rounds after which a commission change requested with `set_commission` applies
+		#[pallet::constant]
+		type CommissionChangeDelay: Get<RoundIndex>;
+		/// Minimum stake for any registered on-chain account to nominate, also the lower bound on
+		/// `min_nominator_stk` in `StakingParams`
+		#[pallet::constant]
+		type MinNomination: Get<BalanceOf<Self>>;
+		/// Staking parameters that the runtime fixed before they were stored in `Params`, written
+		/// to `Params` by the storage migration of chains that predate it
+		type LegacyStakingParams: Get<StakingParams<BalanceOf<Self>>>;
+		/// Handler for the funds slashed from validators and nominators
+		type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// The origin for reporting offences
+		type ReportOrigin: EnsureOrigin<Self::Origin>;
+		/// The origin for cancelling deferred slashes
+		type SlashCancelOrigin: EnsureOrigin<Self::Origin>;
+		/// Fraction of the bond and nominations of an offender that is slashed per offence
+		#[pallet::constant]
+		type SlashFraction: Get<Perbill>;
+		/// Number of rounds that slashes are deferred by after being reported
+		#[pallet::constant]
+		type SlashDeferDuration: Get<RoundIndex>;
+		/// Number of consecutive rounds in which a validator authored none of the blocks it was
+		/// eligible for after which it is made idle, 0 never makes validators idle
+		#[pallet::constant]
+		type MaxMissedRounds: Get<RoundIndex>;
+		/// Amount reserved from a candidate that publishes metadata with `set_metadata`
+		#[pallet::constant]
+		type MetadataDeposit: Get<BalanceOf<Self>>;
+		/// Maximum length in bytes of the display name, website and contact of a candidate
+		#[pallet::constant]
+		type MaxMetadataFieldLength: Get<u32>;
+		/// Maximum length in bytes of the free-form JSON in the metadata of a candidate
+		#[pallet::constant]
+		type MaxMetadataExtraLength: Get<u32>;
+		/// Weight information for extrinsics and round transitions in this pallet
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		BalanceOf<T> = "Balance",
+		T::BlockNumber = "BlockNumber"
+	)]
+	pub enum Event<T: Config> {
 		/// Starting Block, Round, Number of Validators, Total Balance
-		NewRound(BlockNumber, RoundIndex, u32, Balance),
+		NewRound(T::BlockNumber, RoundIndex, u32, BalanceOf<T>),
 		/// Account, Amount Locked, New Total Amt Locked
-		JoinedValidatorCandidates(AccountId, Balance, Balance),
+		JoinedValidatorCandidates(T::AccountId, BalanceOf<T>, BalanceOf<T>),
 		/// Round, Validator Account, Total Exposed Amount (includes all nominations)
-		ValidatorChosen(RoundIndex, AccountId, Balance),
+		ValidatorChosen(RoundIndex, T::AccountId, BalanceOf<T>),
 		/// Validator Account, Old Bond, New Bond
-		ValidatorBondedMore(AccountId, Balance, Balance),
+		ValidatorBondedMore(T::AccountId, BalanceOf<T>, BalanceOf<T>),
 		/// Validator Account, Old Bond, New Bond
-		ValidatorBondedLess(AccountId, Balance, Balance),
-		ValidatorWentOffline(RoundIndex, AccountId),
-		ValidatorBackOnline(RoundIndex, AccountId),
+		ValidatorBondedLess(T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		ValidatorWentOffline(RoundIndex, T::AccountId),
+		ValidatorBackOnline(RoundIndex, T::AccountId),
 		/// Round, Validator Account, Scheduled Exit
-		ValidatorScheduledExit(RoundIndex, AccountId, RoundIndex),
+		ValidatorScheduledExit(RoundIndex, T::AccountId, RoundIndex),
+		/// Validator Account, Old Commission, New Commission, Round Applied
+		CommissionChangeScheduled(T::AccountId, Perbill, Perbill, RoundIndex),
+		/// Validator Account, Old Commission, New Commission
+		CommissionChanged(T::AccountId, Perbill, Perbill),
 		/// Account, Amount Unlocked, New Total Amt Locked
-		ValidatorLeft(AccountId, Balance, Balance),
+		ValidatorLeft(T::AccountId, BalanceOf<T>, BalanceOf<T>),
 		// Nominator, Validator, Old Nomination, New Nomination
-		NominationIncreased(AccountId, AccountId, Balance, Balance),
+		NominationIncreased(T::AccountId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
 		// Nominator, Validator, Old Nomination, New Nomination
-		NominationDecreased(AccountId, AccountId, Balance, Balance),
+		NominationDecreased(T::AccountId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
 		// Nominator, Swapped Amount, Old Nominator, New Nominator
-		NominationSwapped(AccountId, Balance, AccountId, AccountId),
+		NominationSwapped(T::AccountId, BalanceOf<T>, T::AccountId, T::AccountId),
 		/// Nominator, Amount Staked
-		NominatorJoined(AccountId, Balance),
+		NominatorJoined(T::AccountId, BalanceOf<T>),
 		/// Nominator, Amount Unstaked
-		NominatorLeft(AccountId, Balance),
+		NominatorLeft(T::AccountId, BalanceOf<T>),
 		/// Nominator, Amount Locked, Validator, New Total Amt Locked
-		ValidatorNominated(AccountId, Balance, AccountId, Balance),
+		ValidatorNominated(T::AccountId, BalanceOf<T>, T::AccountId, BalanceOf<T>),
 		/// Nominator, Validator, Amount Unstaked, New Total Amt Staked for Validator
-		NominatorLeftValidator(AccountId, AccountId, Balance, Balance),
+		NominatorLeftValidator(T::AccountId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// Nominator, Validator, Amount Unbonding, Round Unbonded
+		NominationUnbonding(T::AccountId, T::AccountId, BalanceOf<T>, RoundIndex),
+		/// Nominator, Amount Unreserved
+		Unbonded(T::AccountId, BalanceOf<T>),
 		/// Paid the account (nominator or validator) the balance as liquid rewards
-		Rewarded(AccountId, Balance),
+		Rewarded(T::AccountId, BalanceOf<T>),
+		/// Account (nominator or validator), Fraction of Rewards Bonded Back
+		AutoCompoundSet(T::AccountId, Perbill),
+		/// Account (nominator or validator), Validator, Amount of Rewards Bonded Back
+		Compounded(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// Account (nominator or validator), Destination of Rewards
+		RewardDestinationSet(T::AccountId, RewardDestination<T::AccountId>),
 		/// Round inflation range set with the provided annual inflation range
 		RoundInflationSet(Perbill, Perbill, Perbill),
 		/// Staking expectations set
-		StakeExpectationsSet(Balance, Balance, Balance),
+		StakeExpectationsSet(Expectations<BalanceOf<T>>),
+		/// Curve used to compute the round issuance from the total staked
+		IssuanceCurveSet(IssuanceCurve),
+		/// Old Parachain Bond Account, New Parachain Bond Account
+		ParachainBondAccountSet(T::AccountId, T::AccountId),
+		/// Old Parachain Bond Reserve Percent, New Parachain Bond Reserve Percent
+		ParachainBondReservePercentSet(Percent, Percent),
+		/// Round, Parachain Bond Account, Amount Reserved from the Round Issuance
+		ReservedForParachainBond(RoundIndex, T::AccountId, BalanceOf<T>),
+		/// Staking Parameters that apply at the start of the next round
+		StakingParamsScheduled(StakingParams<BalanceOf<T>>),
+		/// Old Staking Parameters, New Staking Parameters
+		StakingParamsSet(StakingParams<BalanceOf<T>>, StakingParams<BalanceOf<T>>),
+		/// Round of Offence, Validator Account, Slash Fraction
+		OffenceReported(RoundIndex, T::AccountId, Perbill),
+		/// Round of Application, Validator Account
+		SlashDeferred(RoundIndex, T::AccountId),
+		/// Round of Application, Validator Account
+		SlashCancelled(RoundIndex, T::AccountId),
+		/// Account (validator or nominator), Amount Slashed
+		Slashed(T::AccountId, BalanceOf<T>),
+		/// Round, Validator Account, Slots Eligible, Blocks Authored, Points Awarded
+		UptimeRecorded(RoundIndex, T::AccountId, u32, u32, RewardPoint),
+		/// Nominator, Validator, Amount Unbonding (evicted by a larger nomination)
+		NominationEvicted(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// Round, Validator Account, Consecutive Rounds Without Authoring
+		ValidatorIdled(RoundIndex, T::AccountId, RoundIndex),
+		/// Validator Account, Deposit Reserved for its Metadata
+		MetadataSet(T::AccountId, BalanceOf<T>),
+		/// Validator Account, Deposit Returned
+		MetadataCleared(T::AccountId, BalanceOf<T>),
+		/// Stash Account of a Validator Candidate, Controller Account
+		ControllerSet(T::AccountId, T::AccountId),
+		/// Stash Account of a Validator Candidate, Author Key
+		AuthorKeySet(T::AccountId, T::AccountId),
 	}
-);
 
-decl_error! {
-	pub enum Error for Module<T: Config> {
+	#[pallet::error]
+	pub enum Error<T> {
 		// Nominator Does Not Exist
 		NominatorDNE,
 		CandidateDNE,
@@ -518,299 +871,808 @@ decl_error! {
 		Underflow,
 		CannotSwitchToSameNomination,
 		InvalidSchedule,
+		NoSnapshotForOffence,
+		OffenceAlreadyReported,
+		EmptySlashIndices,
+		InvalidSlashIndex,
+		RoundNotClaimable,
+		RewardsDNE,
+		StakerDNE,
+		InvalidStakingParams,
+		MetadataTooLong,
+		MetadataDNE,
+		ControllerInUse,
+		AuthorKeyInUse,
 	}
-}
 
-decl_storage! {
-	trait Store for Module<T: Config> as Stake {
-		/// Current round, incremented every `BlocksPerRound` in `fn on_finalize`
-		Round: RoundIndex;
-		/// Current nominators with their validator
-		Nominators: map
-			hasher(blake2_128_concat) T::AccountId => Option<Nominator<T::AccountId, BalanceOf<T>>>;
-		/// Current candidates with associated state
-		Candidates: map hasher(blake2_128_concat) T::AccountId => Option<Candidate<T>>;
-		/// Current validator set
-		Validators get(fn validators): Vec<T::AccountId>;
-		/// Total Locked
-		Total: BalanceOf<T>;
-		/// Pool of candidates, ordered by account id
-		CandidatePool: OrderedSet<Bond<T::AccountId,BalanceOf<T>>>;
-		/// Queue of validator exit requests, ordered by account id
-		ExitQueue: OrderedSet<Bond<T::AccountId,RoundIndex>>;
-		/// Exposure at stake per round, per validator
-		AtStake: double_map
-			hasher(blake2_128_concat) RoundIndex,
-			hasher(blake2_128_concat) T::AccountId => ValidatorSnapshot<T::AccountId, BalanceOf<T>>;
-		/// Snapshot of total staked in this round; used to determine round issuance
-		Staked: map
-			hasher(blake2_128_concat) RoundIndex => BalanceOf<T>;
-		/// Inflation parameterization, which contains round issuance and stake expectations
-		InflationConfig get(fn inflation_config) config(): InflationInfo<BalanceOf<T>>;
-		/// Total points awarded in this round
-		Points: map
-			hasher(blake2_128_concat) RoundIndex => RewardPoint;
-		/// Individual points accrued each round per validator
-		AwardedPts: double_map
-			hasher(blake2_128_concat) RoundIndex,
-			hasher(blake2_128_concat) T::AccountId => RewardPoint;
+	/// Current round index, first block and length, the round changes in `fn on_finalize` of its
+	/// last block
+	#[pallet::storage]
+	#[pallet::getter(fn round)]
+	pub type Round<T: Config> = StorageValue<_, RoundInfo<T::BlockNumber>, ValueQuery>;
+
+	/// Current nominators with their validator
+	#[pallet::storage]
+	#[pallet::getter(fn nominator_state)]
+	pub type Nominators<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Nominator<T::AccountId, BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	/// Current candidates with associated state
+	#[pallet::storage]
+	#[pallet::getter(fn candidate_state)]
+	pub type Candidates<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Candidate<T>, OptionQuery>;
+
+	/// Metadata published by validator candidates, removed when they leave
+	#[pallet::storage]
+	#[pallet::getter(fn candidate_metadata)]
+	pub type Metadata<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, CandidateMetadata<BalanceOf<T>>, OptionQuery>;
+
+	/// Controller account of each validator candidate (stash) that designated one
+	#[pallet::storage]
+	#[pallet::getter(fn controller)]
+	pub type Controllers<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+	/// Stash controlled by each controller account, the reverse of `Controllers`
+	#[pallet::storage]
+	#[pallet::getter(fn stash_of_controller)]
+	pub type Stashes<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+	/// Key that each validator candidate (stash) that designated one claims its blocks with in
+	/// `author_inherent`
+	#[pallet::storage]
+	#[pallet::getter(fn author_key)]
+	pub type AuthorKeys<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+	/// Stash of each author key, the reverse of `AuthorKeys`
+	#[pallet::storage]
+	pub type AuthorOf<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+	/// Fraction of the rewards of each validator candidate or nominator that is bonded back
+	#[pallet::storage]
+	#[pallet::getter(fn auto_compound)]
+	pub type AutoCompound<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Perbill, ValueQuery>;
+
+	/// Where the rewards of each validator candidate or nominator are paid
+	#[pallet::storage]
+	#[pallet::getter(fn reward_destination)]
+	pub type Payee<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, RewardDestination<T::AccountId>, ValueQuery>;
+
+	/// Current validator set
+	#[pallet::storage]
+	#[pallet::getter(fn validators)]
+	pub type Validators<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+	/// Total Locked
+	#[pallet::storage]
+	pub type Total<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// Pool of candidates eligible for selection, with their total backing stake
+	#[pallet::storage]
+	#[pallet::getter(fn candidate_pool)]
+	pub type CandidatePool<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
+
+	/// Candidates in the pool, iterated in order of total backing stake (greatest first)
+	#[pallet::storage]
+	pub type CandidatesByStake<T: Config> =
+		StorageDoubleMap<_, Identity, [u8; 16], Blake2_128Concat, T::AccountId, (), ValueQuery>;
+
+	/// Queue of validator exit requests, ordered by account id
+	#[pallet::storage]
+	pub type ExitQueue<T: Config> =
+		StorageValue<_, OrderedSet<Bond<T::AccountId, RoundIndex>>, ValueQuery>;
+
+	/// Commission of each candidate that applies at the start of the inner round
+	#[pallet::storage]
+	#[pallet::getter(fn pending_commission)]
+	pub type PendingCommission<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (Perbill, RoundIndex), OptionQuery>;
+
+	/// Candidates with a commission change that applies at the start of the round
+	#[pallet::storage]
+	pub type ScheduledCommissions<T: Config> =
+		StorageMap<_, Blake2_128Concat, RoundIndex, Vec<T::AccountId>, ValueQuery>;
+
+	/// Stake of each nominator waiting to be unreserved, ordered by round
+	#[pallet::storage]
+	pub type Unbonding<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Vec<UnbondingChunk<T::AccountId, BalanceOf<T>>>,
+		ValueQuery,
+	>;
+
+	/// Nominators with unbonding stake that becomes liquid at the start of the round
+	#[pallet::storage]
+	pub type UnbondingQueue<T: Config> =
+		StorageMap<_, Blake2_128Concat, RoundIndex, Vec<T::AccountId>, ValueQuery>;
+
+	/// Exposure at stake per round, per validator, pruned `HistoryDepth` rounds later
+	#[pallet::storage]
+	pub type AtStake<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		RoundIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		ValidatorSnapshot<T::AccountId, BalanceOf<T>>,
+		ValueQuery,
+	>;
+
+	/// Snapshot of total staked in this round; used to determine round issuance. Pruned
+	/// `HistoryDepth` rounds later.
+	#[pallet::storage]
+	pub type Staked<T: Config> =
+		StorageMap<_, Blake2_128Concat, RoundIndex, BalanceOf<T>, ValueQuery>;
+
+	/// Snapshot of the round inflation range when the round started; used to determine round
+	/// issuance. Pruned `HistoryDepth` rounds later.
+	#[pallet::storage]
+	#[pallet::getter(fn round_inflation)]
+	pub type RoundInflation<T: Config> =
+		StorageMap<_, Blake2_128Concat, RoundIndex, Range<Perbill>, OptionQuery>;
+
+	/// Issuance of each round whose rewards can be claimed, removed once the claims expire
+	#[pallet::storage]
+	#[pallet::getter(fn round_issuance)]
+	pub type RoundIssuance<T: Config> =
+		StorageMap<_, Blake2_128Concat, RoundIndex, BalanceOf<T>, OptionQuery>;
+
+	/// Inflation parameterization, which contains round issuance and stake expectations
+	#[pallet::storage]
+	#[pallet::getter(fn inflation_config)]
+	pub type InflationConfig<T: Config> = StorageValue<_, InflationInfo<BalanceOf<T>>, ValueQuery>;
+
+	/// Curve used to compute the round issuance from the total staked, see `IssuanceCurve`
+	#[pallet::storage]
+	#[pallet::getter(fn issuance_curve)]
+	pub type Curve<T: Config> = StorageValue<_, IssuanceCurve, ValueQuery>;
+
+	/// Staking parameters of the current round
+	#[pallet::storage]
+	#[pallet::getter(fn staking_params)]
+	pub type Params<T: Config> = StorageValue<_, StakingParams<BalanceOf<T>>, ValueQuery>;
+
+	/// Staking parameters that apply at the start of the next round
+	#[pallet::storage]
+	#[pallet::getter(fn pending_params)]
+	pub type PendingParams<T: Config> =
+		StorageValue<_, StakingParams<BalanceOf<T>>, OptionQuery>;
+
+	/// Reserve account and percent of the round issuance that it receives
+	#[pallet::storage]
+	#[pallet::getter(fn parachain_bond_info)]
+	pub type ParachainBondInfo<T: Config> =
+		StorageValue<_, ParachainBondConfig<T::AccountId>, ValueQuery>;
+
+	/// Total points awarded in this round, pruned `HistoryDepth` rounds later
+	#[pallet::storage]
+	pub type Points<T: Config> =
+		StorageMap<_, Blake2_128Concat, RoundIndex, RewardPoint, ValueQuery>;
+
+	/// Individual points accrued each round per validator, removed once rewards are claimed or
+	/// pruned `HistoryDepth` rounds later
+	#[pallet::storage]
+	pub type AwardedPts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		RoundIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		RewardPoint,
+		ValueQuery,
+	>;
+
+	/// Slots for which each validator was eligible and blocks it authored, per round. The points
+	/// of the round are awarded from it when the round ends and it is pruned `HistoryDepth`
+	/// rounds later.
+	#[pallet::storage]
+	#[pallet::getter(fn uptime)]
+	pub type Uptime<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		RoundIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		UptimeInfo,
+		ValueQuery,
+	>;
+
+	/// Validators of the current round with the number of consecutive rounds in which they
+	/// authored none of the blocks they were eligible for
+	#[pallet::storage]
+	#[pallet::getter(fn missed_rounds)]
+	pub type MissedRounds<T: Config> =
+		StorageValue<_, Vec<(T::AccountId, RoundIndex)>, ValueQuery>;
+
+	/// Validators reported for an offence, per round of the offence
+	#[pallet::storage]
+	pub type Offenders<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		RoundIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		bool,
+		ValueQuery,
+	>;
+
+	/// Oldest round whose history has not been pruned completely
+	#[pallet::storage]
+	#[pallet::getter(fn oldest_round)]
+	pub type OldestRound<T: Config> = StorageValue<_, RoundIndex, ValueQuery>;
+
+	/// Slashes waiting to be applied, per round of application
+	#[pallet::storage]
+	pub type UnappliedSlashes<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		RoundIndex,
+		Vec<UnappliedSlash<T::AccountId, BalanceOf<T>>>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn storage_version)]
+	/// Version of the layout of the pallet storage, upgraded by `on_runtime_upgrade`
+	pub type StorageVersion<T: Config> = StorageValue<_, Releases, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Validator candidates (without a nominated validator) with their bond and commission,
+		/// 0% if unspecified, and nominators with the bond of one nomination. A nominator appears
+		/// once for every validator it nominates.
+		pub stakers: Vec<(
+			T::AccountId,
+			Option<T::AccountId>,
+			BalanceOf<T>,
+			Option<Perbill>,
+		)>,
+		/// Inflation parameterization at genesis
+		pub inflation_config: InflationInfo<BalanceOf<T>>,
+		/// Staking parameters at genesis
+		pub params: StakingParams<BalanceOf<T>>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self {
+				stakers: vec![],
+				inflation_config: Default::default(),
+				params: Default::default(),
+			}
+		}
 	}
-	add_extra_genesis {
-		config(stakers):
-			Vec<(T::AccountId,Option<T::AccountId>,BalanceOf<T>)>;
-		build(|config: &GenesisConfig<T>| {
-			for &(ref actor, ref opt_val, balance) in &config.stakers {
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			<StorageVersion<T>>::put(Releases::V4_0_0);
+			<InflationConfig<T>>::put(self.inflation_config.clone());
+			assert!(
+				<Pallet<T>>::staking_params_in_bounds(&self.params),
+				"Staking parameters are out of bounds."
+			);
+			<Params<T>>::put(self.params.clone());
+			for &(ref actor, ref opt_val, balance, fee) in &self.stakers {
 				assert!(
 					T::Currency::free_balance(&actor) >= balance,
-					"Account does not have enough balance to bond."
+					"Account {:?} does not have enough balance to bond {:?}.",
+					actor,
+					balance
 				);
-				let _ = if let Some(nominated_val) = opt_val {
-					<Module<T>>::join_nominators(
-						T::Origin::from(Some(actor.clone()).into()),
-						nominated_val.clone(),
-						balance,
-					)
+				let origin = T::Origin::from(Some(actor.clone()).into());
+				if let Some(nominated_val) = opt_val {
+					assert!(fee.is_none(), "Nominator {:?} cannot set a commission.", actor);
+					let result = if <Pallet<T>>::is_nominator(actor) {
+						<Pallet<T>>::nominate_new(origin, nominated_val.clone(), balance)
+					} else {
+						<Pallet<T>>::join_nominators(origin, nominated_val.clone(), balance)
+					};
+					if let Err(e) = result {
+						panic!(
+							"Genesis nominator {:?} failed to nominate {:?}: {:?}",
+							actor, nominated_val, e.error
+						);
+					}
 				} else {
-					<Module<T>>::join_candidates(
-						T::Origin::from(Some(actor.clone()).into()),
-						Perbill::zero(), // default fee for validators registered at genesis is 0%
-						balance,
-					)
-				};
+					// default fee for validators registered at genesis is 0%
+					let fee = fee.unwrap_or_else(Perbill::zero);
+					if let Err(e) = <Pallet<T>>::join_candidates(origin, fee, balance) {
+						panic!(
+							"Genesis candidate {:?} failed to join candidates: {:?}",
+							actor, e.error
+						);
+					}
+				}
 			}
 			// Choose top `MaxValidator`s from validator candidates
-			let (v_count, total_staked) = <Module<T>>::best_candidates_become_validators(1u32);
-			// Start Round 1 at Block 0
-			<Round>::put(1u32);
+			let (v_count, total_staked) = <Pallet<T>>::best_candidates_become_validators(1u32);
+			// Start Round 1 at Block 1
+			let first_block = <frame_system::Module<T>>::block_number() + 1u32.into();
+			<Round<T>>::put(RoundInfo::new(1u32, first_block, self.params.blocks_per_round));
 			// Snapshot total stake
 			<Staked<T>>::insert(1u32, <Total<T>>::get());
-			<Module<T>>::deposit_event(
-				RawEvent::NewRound(T::BlockNumber::zero(), 1u32, v_count, total_staked)
-			);
-		});
+			<RoundInflation<T>>::insert(1u32, self.inflation_config.round.clone());
+			<Pallet<T>>::deposit_event(Event::NewRound(
+				T::BlockNumber::zero(),
+				1u32,
+				v_count,
+				total_staked,
+			));
+		}
 	}
-}
 
-decl_module! {
-	pub struct Module<T: Config> for enum Call where origin: T::Origin {
-		type Error = Error<T>;
-		fn deposit_event() = default;
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::migrate::<T>()
+		}
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let mut weight = Self::prune_history();
+			// the round transition is executed in `on_finalize` of the same block
+			let round = <Round<T>>::get();
+			if round.should_update(n) {
+				// the queues executed in the transition are unbounded, their length is read here
+				let next = round.current + 1;
+				let exits = <ExitQueue<T>>::get()
+					.0
+					.iter()
+					.filter(|x| x.amount <= next)
+					.count() as u32;
+				let unbonds = <UnbondingQueue<T>>::decode_len(next).unwrap_or_default() as u32;
+				let slashes = <UnappliedSlashes<T>>::decode_len(next).unwrap_or_default() as u32;
+				weight += T::WeightInfo::round_transition(
+					T::MaxValidators::get(),
+					T::MaxNominatorsPerValidator::get(),
+					exits,
+					unbonds,
+					slashes,
+				);
+				weight += T::DbWeight::get().reads(3);
+			}
+			weight
+		}
+		fn integrity_test() {
+			assert!(
+				T::HistoryDepth::get() >= T::BondDuration::get() + T::RewardClaimWindow::get(),
+				"History must be kept until the rewards expire."
+			);
+		}
+		fn on_finalize(n: T::BlockNumber) {
+			let round = <Round<T>>::get();
+			if round.should_update(n) {
+				let next = round.current + 1;
+				// award the points of the ending round from the uptime of its validators
+				Self::award_uptime_points(round.current);
+				// make idle the validators that stopped authoring blocks
+				Self::idle_unresponsive_validators(round.current);
+				// apply all slashes deferred until the next round
+				Self::apply_deferred_slashes(next);
+				// make rewards claimable for T::BondDuration rounds ago and expire old rewards
+				Self::pay_stakers(next);
+				// execute all delayed validator exits
+				Self::execute_delayed_validator_exits(next);
+				// unreserve all nominator stake that finished unbonding
+				Self::execute_delayed_unbonds(next);
+				// apply all commission changes scheduled for the next round
+				Self::execute_delayed_commission_changes(next);
+				// apply the staking parameters scheduled for the next round
+				Self::apply_pending_params();
+				// insert exposure for next validator set
+				let (validator_count, total_staked) = Self::best_candidates_become_validators(next);
+				// start next round with the round length in the staking parameters
+				let length = <Params<T>>::get().blocks_per_round;
+				if length != round.length {
+					<InflationConfig<T>>::mutate(|config| {
+						config.set_round_length(length, T::BlockTime::get())
+					});
+				}
+				<Round<T>>::put(RoundInfo::new(next, n + 1u32.into(), length));
+				// snapshot total stake and round inflation
+				<Staked<T>>::insert(next, <Total<T>>::get());
+				<RoundInflation<T>>::insert(next, <InflationConfig<T>>::get().round);
+				Self::deposit_event(Event::NewRound(n, next, validator_count, total_staked));
+			}
+		}
+	}
 
-		/// A new round chooses a new validator set. Runtime config is 20 so every 2 minutes.
-		const BlocksPerRound: u32 = T::BlocksPerRound::get();
-		/// Number of rounds that validators remain bonded before exit request is executed
-		const BondDuration: RoundIndex = T::BondDuration::get();
-		/// Maximum validators per round.
-		const MaxValidators: u32 = T::MaxValidators::get();
-		/// Maximum nominators per validator
-		const MaxNominatorsPerValidator: u32 = T::MaxNominatorsPerValidator::get();
-		/// Maximum validators per nominator
-		const MaxValidatorsPerNominator: u32 = T::MaxValidatorsPerNominator::get();
-		/// Maximum fee for any validator
-		const MaxFee: Perbill = T::MaxFee::get();
-		/// Minimum stake for any registered on-chain account to become a validator
-		const MinValidatorStk: BalanceOf<T> = T::MinValidatorStk::get();
-		/// Minimum stake for any registered on-chain account to nominate
-		const MinNomination: BalanceOf<T> = T::MinNomination::get();
-		/// Minimum stake for any registered on-chain account to become a nominator
-		const MinNominatorStk: BalanceOf<T> = T::MinNominatorStk::get();
-
-		/// Set the expectations for total staked. These expectations determine the issuance for
-		/// the round according to logic in `fn compute_issuance`
-		#[weight = 0]
-		fn set_staking_expectations(
-			origin,
-			expectations: Range<BalanceOf<T>>,
-		) -> DispatchResult {
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the expectations for total staked, as amounts or as fractions of the total
+		/// issuance. These expectations determine the issuance for the round according to logic
+		/// in `fn compute_issuance`
+		#[pallet::weight(T::WeightInfo::set_staking_expectations())]
+		pub fn set_staking_expectations(
+			origin: OriginFor<T>,
+			expectations: Expectations<BalanceOf<T>>,
+		) -> DispatchResultWithPostInfo {
 			T::SetMonetaryPolicyOrigin::ensure_origin(origin)?;
 			ensure!(expectations.is_valid(), Error::<T>::InvalidSchedule);
 			let mut config = <InflationConfig<T>>::get();
-			config.set_expectations(expectations);
-			Self::deposit_event(
-				RawEvent::StakeExpectationsSet(
-					config.expect.min,
-					config.expect.ideal,
-					config.expect.max
-				)
-			);
+			config.set_expectations(expectations.clone());
+			Self::deposit_event(Event::StakeExpectationsSet(expectations));
 			<InflationConfig<T>>::put(config);
-			Ok(())
+			Ok(().into())
 		}
 		/// (Re)set the annual inflation and update round inflation range in storage
-		#[weight = 0]
-		fn set_inflation(
-			origin,
-			schedule: Range<Perbill>
-		) -> DispatchResult {
+		#[pallet::weight(T::WeightInfo::set_inflation())]
+		pub fn set_inflation(
+			origin: OriginFor<T>,
+			schedule: Range<Perbill>,
+		) -> DispatchResultWithPostInfo {
 			T::SetMonetaryPolicyOrigin::ensure_origin(origin)?;
 			ensure!(schedule.is_valid(), Error::<T>::InvalidSchedule);
 			let mut config = <InflationConfig<T>>::get();
-			config.set_annual_rate::<T>(schedule);
-			Self::deposit_event(
-				RawEvent::RoundInflationSet(
-					config.round.min,
-					config.round.ideal,
-					config.round.max,
-				)
-			);
+			config.set_annual_rate(schedule, <Round<T>>::get().length, T::BlockTime::get());
+			Self::deposit_event(Event::RoundInflationSet(
+				config.round.min,
+				config.round.ideal,
+				config.round.max,
+			));
 			<InflationConfig<T>>::put(config);
-			Ok(())
+			Ok(().into())
 		}
-		/// Join the set of validator candidates by bonding at least `MinValidatorStk` and
-		/// setting commission fee below the `MaxFee`
-		#[weight = 0]
-		fn join_candidates(
-			origin,
-			fee: Perbill,
-			bond: BalanceOf<T>,
-		) -> DispatchResult {
-			let acc = ensure_signed(origin)?;
-			ensure!(!Self::is_candidate(&acc),Error::<T>::CandidateExists);
-			ensure!(!Self::is_nominator(&acc),Error::<T>::NominatorExists);
-			ensure!(fee <= T::MaxFee::get(),Error::<T>::FeeOverMax);
-			ensure!(bond >= T::MinValidatorStk::get(),Error::<T>::ValBondBelowMin);
-			let mut candidates = <CandidatePool<T>>::get();
+		/// Set the curve that maps the total staked in a round to the issuance for the round
+		#[pallet::weight(T::WeightInfo::set_issuance_curve())]
+		pub fn set_issuance_curve(
+			origin: OriginFor<T>,
+			curve: IssuanceCurve,
+		) -> DispatchResultWithPostInfo {
+			T::SetMonetaryPolicyOrigin::ensure_origin(origin)?;
+			<Curve<T>>::put(curve);
+			Self::deposit_event(Event::IssuanceCurveSet(curve));
+			Ok(().into())
+		}
+		/// Set the account that receives the parachain bond reserve of the round issuance
+		#[pallet::weight(T::WeightInfo::set_parachain_bond_account())]
+		pub fn set_parachain_bond_account(
+			origin: OriginFor<T>,
+			new: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			T::SetMonetaryPolicyOrigin::ensure_origin(origin)?;
+			let old = <ParachainBondInfo<T>>::mutate(|info| {
+				sp_std::mem::replace(&mut info.account, new.clone())
+			});
+			Self::deposit_event(Event::ParachainBondAccountSet(old, new));
+			Ok(().into())
+		}
+		/// Set the percent of the round issuance reserved for the parachain bond account
+		#[pallet::weight(T::WeightInfo::set_parachain_bond_reserve_percent())]
+		pub fn set_parachain_bond_reserve_percent(
+			origin: OriginFor<T>,
+			new: Percent,
+		) -> DispatchResultWithPostInfo {
+			T::SetMonetaryPolicyOrigin::ensure_origin(origin)?;
+			let old = <ParachainBondInfo<T>>::mutate(|info| {
+				sp_std::mem::replace(&mut info.percent, new)
+			});
+			Self::deposit_event(Event::ParachainBondReservePercentSet(old, new));
+			Ok(().into())
+		}
+		/// Set the staking parameters that apply at the start of the next round. Candidates and
+		/// nominators are not removed if their stake or number falls outside the new parameters.
+		#[pallet::weight(T::WeightInfo::set_staking_params())]
+		pub fn set_staking_params(
+			origin: OriginFor<T>,
+			params: StakingParams<BalanceOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			T::SetMonetaryPolicyOrigin::ensure_origin(origin)?;
 			ensure!(
-				candidates.insert(Bond{owner: acc.clone(), amount: bond}),
-				Error::<T>::CandidateExists
+				Self::staking_params_in_bounds(&params),
+				Error::<T>::InvalidStakingParams
 			);
+			<PendingParams<T>>::put(params.clone());
+			Self::deposit_event(Event::StakingParamsScheduled(params));
+			Ok(().into())
+		}
+		/// Join the set of validator candidates by bonding at least `min_validator_stk` and
+		/// setting commission fee below the `MaxFee`
+		#[pallet::weight(T::WeightInfo::join_candidates())]
+		pub fn join_candidates(
+			origin: OriginFor<T>,
+			fee: Perbill,
+			bond: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let acc = ensure_signed(origin)?;
+			ensure!(!Self::is_candidate(&acc), Error::<T>::CandidateExists);
+			ensure!(!Self::is_nominator(&acc), Error::<T>::NominatorExists);
+			ensure!(!<Stashes<T>>::contains_key(&acc), Error::<T>::ControllerInUse);
+			ensure!(!<AuthorOf<T>>::contains_key(&acc), Error::<T>::AuthorKeyInUse);
+			ensure!(fee <= T::MaxFee::get(), Error::<T>::FeeOverMax);
+			ensure!(
+				bond >= <Params<T>>::get().min_validator_stk,
+				Error::<T>::ValBondBelowMin
+			);
+			ensure!(!<CandidatePool<T>>::contains_key(&acc), Error::<T>::CandidateExists);
 			T::Currency::reserve(&acc, bond)?;
-			let candidate: Candidate<T> = Validator::new(acc.clone(), fee, bond);
+			let joined = <Round<T>>::get().current;
+			let candidate: Candidate<T> = Validator::new(acc.clone(), fee, bond, joined);
 			let new_total = <Total<T>>::get() + bond;
 			<Total<T>>::put(new_total);
 			<Candidates<T>>::insert(&acc, candidate);
-			<CandidatePool<T>>::put(candidates);
-			Self::deposit_event(RawEvent::JoinedValidatorCandidates(acc, bond, new_total));
-			Ok(())
+			Self::update_active(acc.clone(), bond);
+			Self::deposit_event(Event::JoinedValidatorCandidates(acc, bond, new_total));
+			Ok(().into())
 		}
 		/// Request to leave the set of candidates. If successful, the account is immediately
 		/// removed from the candidate pool to prevent selection as a validator, but unbonding is
 		/// executed with a delay of `BondDuration` rounds.
-		#[weight = 0]
-		fn leave_candidates(origin) -> DispatchResult {
+		#[pallet::weight(T::WeightInfo::leave_candidates())]
+		pub fn leave_candidates(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
 			let validator = ensure_signed(origin)?;
 			let mut state = <Candidates<T>>::get(&validator).ok_or(Error::<T>::CandidateDNE)?;
-			ensure!(!state.is_leaving(),Error::<T>::AlreadyLeaving);
+			ensure!(!state.is_leaving(), Error::<T>::AlreadyLeaving);
 			let mut exits = <ExitQueue<T>>::get();
-			let now = <Round>::get();
+			let now = <Round<T>>::get().current;
 			let when = now + T::BondDuration::get();
 			ensure!(
-				exits.insert(Bond{owner:validator.clone(),amount:when}),
+				exits.insert(Bond {
+					owner: validator.clone(),
+					amount: when
+				}),
 				Error::<T>::AlreadyLeaving
 			);
 			state.leave_candidates(when);
-			let mut candidates = <CandidatePool<T>>::get();
-			if candidates.remove(&Bond::from_owner(validator.clone())) {
-				<CandidatePool<T>>::put(candidates);
-			}
+			Self::remove_from_pool(&validator);
 			<ExitQueue<T>>::put(exits);
-			<Candidates<T>>::insert(&validator,state);
-			Self::deposit_event(RawEvent::ValidatorScheduledExit(now,validator,when));
-			Ok(())
+			<Candidates<T>>::insert(&validator, state);
+			Self::deposit_event(Event::ValidatorScheduledExit(now, validator, when));
+			Ok(().into())
 		}
 		/// Temporarily leave the set of validator candidates without unbonding
-		#[weight = 0]
-		fn go_offline(origin) -> DispatchResult {
-			let validator = ensure_signed(origin)?;
+		#[pallet::weight(T::WeightInfo::go_offline())]
+		pub fn go_offline(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let validator = Self::ensure_stash(origin)?;
 			let mut state = <Candidates<T>>::get(&validator).ok_or(Error::<T>::CandidateDNE)?;
-			ensure!(state.is_active(),Error::<T>::AlreadyOffline);
+			ensure!(state.is_active(), Error::<T>::AlreadyOffline);
 			state.go_offline();
-			let mut candidates = <CandidatePool<T>>::get();
-			// TODO: investigate possible bug in this next line
-			if candidates.remove(&Bond::from_owner(validator.clone())) {
-				<CandidatePool<T>>::put(candidates);
-			}
-			<Candidates<T>>::insert(&validator,state);
-			Self::deposit_event(RawEvent::ValidatorWentOffline(<Round>::get(),validator));
-			Ok(())
+			Self::remove_from_pool(&validator);
+			<Candidates<T>>::insert(&validator, state);
+			let round = <Round<T>>::get().current;
+			Self::deposit_event(Event::ValidatorWentOffline(round, validator));
+			Ok(().into())
 		}
-		/// Rejoin the set of validator candidates if previously had called `go_offline`
-		#[weight = 0]
-		fn go_online(origin) -> DispatchResult {
-			let validator = ensure_signed(origin)?;
+		/// Rejoin the set of validator candidates if previously had called `go_offline` or was made
+		/// idle for authoring no block in `MaxMissedRounds` consecutive rounds
+		#[pallet::weight(T::WeightInfo::go_online())]
+		pub fn go_online(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let validator = Self::ensure_stash(origin)?;
 			let mut state = <Candidates<T>>::get(&validator).ok_or(Error::<T>::CandidateDNE)?;
-			ensure!(!state.is_active(),Error::<T>::AlreadyActive);
-			ensure!(!state.is_leaving(),Error::<T>::CannotActivateIfLeaving);
+			ensure!(!state.is_active(), Error::<T>::AlreadyActive);
+			ensure!(!state.is_leaving(), Error::<T>::CannotActivateIfLeaving);
 			state.go_online();
-			let mut candidates = <CandidatePool<T>>::get();
-			ensure!(
-				candidates.insert(Bond{owner:validator.clone(),amount:state.total}),
-				Error::<T>::AlreadyActive
-			);
-			<CandidatePool<T>>::put(candidates);
-			<Candidates<T>>::insert(&validator,state);
-			Self::deposit_event(RawEvent::ValidatorBackOnline(<Round>::get(),validator));
-			Ok(())
+			ensure!(!<CandidatePool<T>>::contains_key(&validator), Error::<T>::AlreadyActive);
+			Self::update_active(validator.clone(), state.total);
+			<Candidates<T>>::insert(&validator, state);
+			let round = <Round<T>>::get().current;
+			Self::deposit_event(Event::ValidatorBackOnline(round, validator));
+			Ok(().into())
 		}
 		/// Bond more for validator candidates
-		#[weight = 0]
-		fn candidate_bond_more(origin, more: BalanceOf<T>) -> DispatchResult {
-			let validator = ensure_signed(origin)?;
+		#[pallet::weight(T::WeightInfo::candidate_bond_more())]
+		pub fn candidate_bond_more(
+			origin: OriginFor<T>,
+			more: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let validator = Self::ensure_stash(origin)?;
 			let mut state = <Candidates<T>>::get(&validator).ok_or(Error::<T>::CandidateDNE)?;
-			ensure!(!state.is_leaving(),Error::<T>::CannotActivateIfLeaving);
+			ensure!(!state.is_leaving(), Error::<T>::CannotActivateIfLeaving);
 			T::Currency::reserve(&validator, more)?;
 			let before = state.bond;
 			state.bond_more(more);
 			let after = state.bond;
+			<Total<T>>::mutate(|total| *total += more);
 			if state.is_active() {
 				Self::update_active(validator.clone(), state.total);
 			}
-			<Candidates<T>>::insert(&validator,state);
-			Self::deposit_event(RawEvent::ValidatorBondedMore(validator, before, after));
-			Ok(())
+			<Candidates<T>>::insert(&validator, state);
+			Self::deposit_event(Event::ValidatorBondedMore(validator, before, after));
+			Ok(().into())
 		}
 		/// Bond less for validator candidates
-		#[weight = 0]
-		fn candidate_bond_less(origin, less: BalanceOf<T>) -> DispatchResult {
-			let validator = ensure_signed(origin)?;
+		#[pallet::weight(T::WeightInfo::candidate_bond_less())]
+		pub fn candidate_bond_less(
+			origin: OriginFor<T>,
+			less: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let validator = Self::ensure_stash(origin)?;
 			let mut state = <Candidates<T>>::get(&validator).ok_or(Error::<T>::CandidateDNE)?;
-			ensure!(!state.is_leaving(),Error::<T>::CannotActivateIfLeaving);
+			ensure!(!state.is_leaving(), Error::<T>::CannotActivateIfLeaving);
 			let before = state.bond;
 			let after = state.bond_less(less).ok_or(Error::<T>::Underflow)?;
-			ensure!(after >= T::MinValidatorStk::get(), Error::<T>::ValBondBelowMin);
+			ensure!(
+				after >= <Params<T>>::get().min_validator_stk,
+				Error::<T>::ValBondBelowMin
+			);
 			T::Currency::unreserve(&validator, less);
+			<Total<T>>::mutate(|total| *total -= less);
 			if state.is_active() {
 				Self::update_active(validator.clone(), state.total);
 			}
 			<Candidates<T>>::insert(&validator, state);
-			Self::deposit_event(RawEvent::ValidatorBondedLess(validator, before, after));
-			Ok(())
+			Self::deposit_event(Event::ValidatorBondedLess(validator, before, after));
+			Ok(().into())
+		}
+		/// Request to change the commission fee of the caller, a validator candidate, to `fee`
+		/// after `CommissionChangeDelay` rounds. Replaces any pending request.
+		#[pallet::weight(T::WeightInfo::set_commission())]
+		pub fn set_commission(origin: OriginFor<T>, fee: Perbill) -> DispatchResultWithPostInfo {
+			let validator = Self::ensure_stash(origin)?;
+			let state = <Candidates<T>>::get(&validator).ok_or(Error::<T>::CandidateDNE)?;
+			ensure!(!state.is_leaving(), Error::<T>::CannotActivateIfLeaving);
+			ensure!(fee <= T::MaxFee::get(), Error::<T>::FeeOverMax);
+			let when = <Round<T>>::get().current + T::CommissionChangeDelay::get();
+			let queued = matches!(
+				<PendingCommission<T>>::get(&validator),
+				Some((_, scheduled)) if scheduled == when
+			);
+			if !queued {
+				<ScheduledCommissions<T>>::mutate(when, |queue| queue.push(validator.clone()));
+			}
+			<PendingCommission<T>>::insert(&validator, (fee, when));
+			Self::deposit_event(Event::CommissionChangeScheduled(
+				validator, state.fee, fee, when,
+			));
+			Ok(().into())
 		}
-		/// Join the set of nominators
-		#[weight = 0]
-		fn join_nominators(
-			origin,
+		/// Publish the display name, website, contact and free-form JSON of the caller, a validator
+		/// candidate, for nominators. `MetadataDeposit` is reserved unless metadata was already
+		/// published, and it is returned by `clear_metadata` or when the candidate leaves.
+		#[pallet::weight(T::WeightInfo::set_metadata())]
+		pub fn set_metadata(
+			origin: OriginFor<T>,
+			display_name: Vec<u8>,
+			website: Vec<u8>,
+			contact: Vec<u8>,
+			extra: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let validator = Self::ensure_stash(origin)?;
+			ensure!(Self::is_candidate(&validator), Error::<T>::CandidateDNE);
+			let max_field = T::MaxMetadataFieldLength::get() as usize;
+			ensure!(
+				display_name.len() <= max_field
+					&& website.len() <= max_field
+					&& contact.len() <= max_field
+					&& extra.len() <= T::MaxMetadataExtraLength::get() as usize,
+				Error::<T>::MetadataTooLong
+			);
+			let deposit = match <Metadata<T>>::get(&validator) {
+				Some(metadata) => metadata.deposit,
+				None => {
+					let deposit = T::MetadataDeposit::get();
+					T::Currency::reserve(&validator, deposit)?;
+					deposit
+				}
+			};
+			<Metadata<T>>::insert(
+				&validator,
+				CandidateMetadata {
+					display_name,
+					website,
+					contact,
+					extra,
+					deposit,
+				},
+			);
+			Self::deposit_event(Event::MetadataSet(validator, deposit));
+			Ok(().into())
+		}
+		/// Remove the metadata of the caller and return its deposit
+		#[pallet::weight(T::WeightInfo::clear_metadata())]
+		pub fn clear_metadata(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let validator = Self::ensure_stash(origin)?;
+			Self::remove_metadata(&validator).ok_or(Error::<T>::MetadataDNE)?;
+			Ok(().into())
+		}
+		/// Designate `controller` to sign `go_offline`, `go_online`, `candidate_bond_more`,
+		/// `candidate_bond_less`, `set_commission`, the metadata calls and `set_author_key` for the
+		/// caller, a validator candidate, which is its stash. The bond stays reserved from and is
+		/// unreserved to the stash. Replaces the previous controller.
+		#[pallet::weight(T::WeightInfo::set_controller())]
+		pub fn set_controller(
+			origin: OriginFor<T>,
+			controller: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let stash = ensure_signed(origin)?;
+			ensure!(Self::is_candidate(&stash), Error::<T>::CandidateDNE);
+			ensure!(
+				!Self::is_candidate(&controller)
+					&& !Self::is_nominator(&controller)
+					&& !<Stashes<T>>::contains_key(&controller),
+				Error::<T>::ControllerInUse
+			);
+			if let Some(old) = <Controllers<T>>::get(&stash) {
+				<Stashes<T>>::remove(&old);
+			}
+			<Controllers<T>>::insert(&stash, &controller);
+			<Stashes<T>>::insert(&controller, &stash);
+			Self::deposit_event(Event::ControllerSet(stash, controller));
+			Ok(().into())
+		}
+		/// Set the key that the validator candidate of the caller, its stash or controller, claims
+		/// its blocks with in `author_inherent`. Blocks claimed by the stash itself are no longer
+		/// accepted, the points of blocks claimed by `author` are awarded to the stash.
+		#[pallet::weight(T::WeightInfo::set_author_key())]
+		pub fn set_author_key(
+			origin: OriginFor<T>,
+			author: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let stash = Self::ensure_stash(origin)?;
+			ensure!(Self::is_candidate(&stash), Error::<T>::CandidateDNE);
+			ensure!(
+				!Self::is_candidate(&author) && !<AuthorOf<T>>::contains_key(&author),
+				Error::<T>::AuthorKeyInUse
+			);
+			if let Some(old) = <AuthorKeys<T>>::get(&stash) {
+				<AuthorOf<T>>::remove(&old);
+			}
+			<AuthorKeys<T>>::insert(&stash, &author);
+			<AuthorOf<T>>::insert(&author, &stash);
+			Self::deposit_event(Event::AuthorKeySet(stash, author));
+			Ok(().into())
+		}
+		/// Join the set of nominators. If the validator is full, the nomination must be larger than
+		/// the lowest nomination of the validator, which it evicts
+		#[pallet::weight(T::WeightInfo::join_nominators(T::MaxNominatorsPerValidator::get()))]
+		pub fn join_nominators(
+			origin: OriginFor<T>,
 			validator: T::AccountId,
 			amount: BalanceOf<T>,
-		) -> DispatchResult {
+		) -> DispatchResultWithPostInfo {
 			let acc = ensure_signed(origin)?;
-			ensure!(amount >= T::MinNominatorStk::get(), Error::<T>::NomBondBelowMin);
-			ensure!(!Self::is_nominator(&acc),Error::<T>::NominatorExists);
-			ensure!(!Self::is_candidate(&acc),Error::<T>::CandidateExists);
+			ensure!(
+				amount >= <Params<T>>::get().min_nominator_stk,
+				Error::<T>::NomBondBelowMin
+			);
+			ensure!(!Self::is_nominator(&acc), Error::<T>::NominatorExists);
+			ensure!(!Self::is_candidate(&acc), Error::<T>::CandidateExists);
+			ensure!(!<Stashes<T>>::contains_key(&acc), Error::<T>::ControllerInUse);
 			Self::nominator_joins_validator(acc.clone(), amount, validator.clone())?;
 			<Nominators<T>>::insert(&acc, Nominator::new(validator, amount));
-			Self::deposit_event(RawEvent::NominatorJoined(acc, amount));
-			Ok(())
+			Self::deposit_event(Event::NominatorJoined(acc, amount));
+			Ok(().into())
 		}
 		/// Leave the set of nominators and, by implication, revoke all ongoing nominations
-		#[weight = 0]
-		fn leave_nominators(origin) -> DispatchResult {
+		#[pallet::weight(T::WeightInfo::leave_nominators(
+			T::MaxValidatorsPerNominator::get(),
+			T::MaxNominatorsPerValidator::get(),
+		))]
+		pub fn leave_nominators(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
 			let acc = ensure_signed(origin)?;
 			let nominator = <Nominators<T>>::get(&acc).ok_or(Error::<T>::NominatorDNE)?;
 			for bond in nominator.nominations.0 {
 				Self::nominator_leaves_validator(acc.clone(), bond.owner.clone())?;
 			}
 			<Nominators<T>>::remove(&acc);
-			Self::deposit_event(RawEvent::NominatorLeft(acc, nominator.total));
-			Ok(())
+			<AutoCompound<T>>::remove(&acc);
+			<Payee<T>>::remove(&acc);
+			Self::deposit_event(Event::NominatorLeft(acc, nominator.total));
+			Ok(().into())
 		}
-		/// Nominate a new validator candidate if already nominating
-		#[weight = 0]
-		fn nominate_new(
-			origin,
+		/// Nominate a new validator candidate if already nominating. If the validator is full, the
+		/// nomination must be larger than the lowest nomination of the validator, which it evicts
+		#[pallet::weight(T::WeightInfo::nominate_new(T::MaxNominatorsPerValidator::get()))]
+		pub fn nominate_new(
+			origin: OriginFor<T>,
 			validator: T::AccountId,
 			amount: BalanceOf<T>,
-		) -> DispatchResult {
+		) -> DispatchResultWithPostInfo {
 			let acc = ensure_signed(origin)?;
 			ensure!(amount >= T::MinNomination::get(), Error::<T>::NominationBelowMin);
 			let mut nominator = <Nominators<T>>::get(&acc).ok_or(Error::<T>::NominatorDNE)?;
@@ -820,22 +1682,22 @@ decl_module! {
 			);
 			let mut state = <Candidates<T>>::get(&validator).ok_or(Error::<T>::CandidateDNE)?;
 			ensure!(
-				nominator.add_nomination(Bond{owner:validator.clone(), amount}),
+				nominator.add_nomination(Bond {
+					owner: validator.clone(),
+					amount
+				}),
 				Error::<T>::AlreadyNominatedValidator
 			);
 			let nomination = Bond {
 				owner: acc.clone(),
 				amount,
 			};
-			ensure!(
-				(state.nominators.0.len() as u32) < T::MaxNominatorsPerValidator::get(),
-				Error::<T>::TooManyNominators
-			);
-			ensure!(
-				state.nominators.insert(nomination),
-				Error::<T>::NominatorExists
-			);
+			let evicted = Self::nomination_to_evict(&state, amount)?;
+			ensure!(state.nominators.insert(nomination), Error::<T>::NominatorExists);
 			T::Currency::reserve(&acc, amount)?;
+			if let Some(evicted) = evicted {
+				Self::evict_nomination(&mut state, &validator, evicted);
+			}
 			let new_total = state.total + amount;
 			if state.is_active() {
 				Self::update_active(validator.clone(), new_total);
@@ -845,15 +1707,18 @@ decl_module! {
 			state.total = new_total;
 			<Candidates<T>>::insert(&validator, state);
 			<Nominators<T>>::insert(&acc, nominator);
-			Self::deposit_event(RawEvent::ValidatorNominated(
-				acc, amount, validator, new_total,
-			));
-			Ok(())
+			Self::deposit_event(Event::ValidatorNominated(acc, amount, validator, new_total));
+			Ok(().into())
 		}
 		/// Swap an old nomination with a new nomination. If the new nomination exists, it
-		/// updates the existing nomination by adding the balance of the old nomination
-		#[weight = 0]
-		fn switch_nomination(origin, old: T::AccountId, new: T::AccountId) -> DispatchResult {
+		/// updates the existing nomination by adding the balance of the old nomination. A new
+		/// nomination of a validator with the maximum number of nominators evicts the lowest one.
+		#[pallet::weight(T::WeightInfo::switch_nomination(T::MaxNominatorsPerValidator::get()))]
+		pub fn switch_nomination(
+			origin: OriginFor<T>,
+			old: T::AccountId,
+			new: T::AccountId,
+		) -> DispatchResultWithPostInfo {
 			let acc = ensure_signed(origin)?;
 			ensure!(old != new, Error::<T>::CannotSwitchToSameNomination);
 			let mut nominator = <Nominators<T>>::get(&acc).ok_or(Error::<T>::NominatorDNE)?;
@@ -863,9 +1728,19 @@ decl_module! {
 				.swap_nomination(old.clone(), new.clone())
 				.ok_or(Error::<T>::NominationDNE)?;
 			let (new_old, new_new) = if new_amt > swapped_amt {
-				(old_validator.rm_nominator(acc.clone()), new_validator.update_nominator(acc.clone(), new_amt))
+				(
+					old_validator.rm_nominator(acc.clone()),
+					new_validator.update_nominator(acc.clone(), new_amt),
+				)
 			} else {
-				(old_validator.rm_nominator(acc.clone()), new_validator.add_nominator(acc.clone(), swapped_amt))
+				// the lowest nomination of a full validator is evicted if the swapped one is larger
+				let evicted = Self::nomination_to_evict(&new_validator, swapped_amt)?;
+				let new_old = old_validator.rm_nominator(acc.clone());
+				new_validator.add_nominator(acc.clone(), swapped_amt);
+				if let Some(evicted) = evicted {
+					Self::evict_nomination(&mut new_validator, &new, evicted);
+				}
+				(new_old, new_validator.total)
 			};
 			if old_validator.is_active() {
 				Self::update_active(old.clone(), new_old);
@@ -876,23 +1751,28 @@ decl_module! {
 			<Candidates<T>>::insert(&old, old_validator);
 			<Candidates<T>>::insert(&new, new_validator);
 			<Nominators<T>>::insert(&acc, nominator);
-			Self::deposit_event(RawEvent::NominationSwapped(acc, swapped_amt, old, new));
-			Ok(())
+			Self::deposit_event(Event::NominationSwapped(acc, swapped_amt, old, new));
+			Ok(().into())
 		}
 		/// Revoke an existing nomination
-		#[weight = 0]
-		fn revoke_nomination(origin, validator: T::AccountId) -> DispatchResult {
-			Self::nominator_revokes_validator(ensure_signed(origin)?, validator)
+		#[pallet::weight(T::WeightInfo::revoke_nomination(T::MaxNominatorsPerValidator::get()))]
+		pub fn revoke_nomination(
+			origin: OriginFor<T>,
+			validator: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			Self::nominator_revokes_validator(ensure_signed(origin)?, validator)?;
+			Ok(().into())
 		}
 		/// Bond more for nominators with respect to a specific validator candidate
-		#[weight = 0]
-		fn nominator_bond_more(
-			origin,
+		#[pallet::weight(T::WeightInfo::nominator_bond_more(T::MaxNominatorsPerValidator::get()))]
+		pub fn nominator_bond_more(
+			origin: OriginFor<T>,
 			candidate: T::AccountId,
-			more: BalanceOf<T>
-		) -> DispatchResult {
+			more: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
 			let nominator = ensure_signed(origin)?;
-			let mut nominations = <Nominators<T>>::get(&nominator).ok_or(Error::<T>::NominatorDNE)?;
+			let mut nominations =
+				<Nominators<T>>::get(&nominator).ok_or(Error::<T>::NominatorDNE)?;
 			let mut validator = <Candidates<T>>::get(&candidate).ok_or(Error::<T>::CandidateDNE)?;
 			let _ = nominations
 				.inc_nomination(candidate.clone(), more)
@@ -901,313 +1781,1050 @@ decl_module! {
 			let before = validator.total;
 			validator.inc_nominator(nominator.clone(), more);
 			let after = validator.total;
+			<Total<T>>::mutate(|total| *total += more);
 			if validator.is_active() {
 				Self::update_active(candidate.clone(), validator.total);
 			}
 			<Candidates<T>>::insert(&candidate, validator);
 			<Nominators<T>>::insert(&nominator, nominations);
-			Self::deposit_event(RawEvent::NominationIncreased(nominator, candidate, before, after));
-			Ok(())
+			Self::deposit_event(Event::NominationIncreased(nominator, candidate, before, after));
+			Ok(().into())
 		}
-		/// Bond less for nominators with respect to a specific nominator candidate
-		#[weight = 0]
-		fn nominator_bond_less(
-			origin,
+		/// Bond less for nominators with respect to a specific nominator candidate. The stake is
+		/// unreserved after `UnbondingDelay` rounds.
+		#[pallet::weight(T::WeightInfo::nominator_bond_less(T::MaxNominatorsPerValidator::get()))]
+		pub fn nominator_bond_less(
+			origin: OriginFor<T>,
 			candidate: T::AccountId,
-			less: BalanceOf<T>
-		) -> DispatchResult {
+			less: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
 			let nominator = ensure_signed(origin)?;
-			let mut nominations = <Nominators<T>>::get(&nominator).ok_or(Error::<T>::NominatorDNE)?;
+			let mut nominations =
+				<Nominators<T>>::get(&nominator).ok_or(Error::<T>::NominatorDNE)?;
 			let mut validator = <Candidates<T>>::get(&candidate).ok_or(Error::<T>::CandidateDNE)?;
 			let remaining = nominations
 				.dec_nomination(candidate.clone(), less)
 				.ok_or(Error::<T>::NominationDNE)?
 				.ok_or(Error::<T>::Underflow)?;
 			ensure!(remaining >= T::MinNomination::get(), Error::<T>::NominationBelowMin);
-			ensure!(nominations.total >= T::MinNominatorStk::get(), Error::<T>::NomBondBelowMin);
-			T::Currency::unreserve(&nominator, less);
+			ensure!(
+				nominations.total >= <Params<T>>::get().min_nominator_stk,
+				Error::<T>::NomBondBelowMin
+			);
 			let before = validator.total;
 			validator.dec_nominator(nominator.clone(), less);
 			let after = validator.total;
+			<Total<T>>::mutate(|total| *total -= less);
 			if validator.is_active() {
 				Self::update_active(candidate.clone(), validator.total);
 			}
 			<Candidates<T>>::insert(&candidate, validator);
 			<Nominators<T>>::insert(&nominator, nominations);
-			Self::deposit_event(RawEvent::NominationDecreased(nominator, candidate, before, after));
-			Ok(())
+			Self::deposit_event(Event::NominationDecreased(
+				nominator.clone(),
+				candidate.clone(),
+				before,
+				after,
+			));
+			Self::schedule_unbond(nominator, candidate, less);
+			Ok(().into())
 		}
-		fn on_finalize(n: T::BlockNumber) {
-			if (n % T::BlocksPerRound::get().into()).is_zero() {
-				let next = <Round>::get() + 1;
-				// pay all stakers for T::BondDuration rounds ago
-				Self::pay_stakers(next);
-				// execute all delayed validator exits
-				Self::execute_delayed_validator_exits(next);
-				// insert exposure for next validator set
-				let (validator_count, total_staked) = Self::best_candidates_become_validators(next);
-				// start next round
-				<Round>::put(next);
-				// snapshot total stake
-				<Staked<T>>::insert(next, <Total<T>>::get());
-				Self::deposit_event(RawEvent::NewRound(n, next, validator_count, total_staked));
+		/// Set the fraction of every reward paid to the caller, as a validator candidate or as a
+		/// nominator, that is bonded back to the validator that earned the reward
+		#[pallet::weight(T::WeightInfo::set_auto_compound())]
+		pub fn set_auto_compound(
+			origin: OriginFor<T>,
+			fraction: Perbill,
+		) -> DispatchResultWithPostInfo {
+			let acc = ensure_signed(origin)?;
+			ensure!(Self::is_candidate(&acc) || Self::is_nominator(&acc), Error::<T>::StakerDNE);
+			if fraction.is_zero() {
+				<AutoCompound<T>>::remove(&acc);
+			} else {
+				<AutoCompound<T>>::insert(&acc, fraction);
 			}
+			Self::deposit_event(Event::AutoCompoundSet(acc, fraction));
+			Ok(().into())
 		}
-	}
-}
-
-impl<T: Config> Module<T> {
-	pub fn is_nominator(acc: &T::AccountId) -> bool {
-		<Nominators<T>>::get(acc).is_some()
-	}
-	pub fn is_candidate(acc: &T::AccountId) -> bool {
-		<Candidates<T>>::get(acc).is_some()
-	}
-	pub fn is_validator(acc: &T::AccountId) -> bool {
-		<Validators<T>>::get().binary_search(acc).is_ok()
-	}
-	// ensure candidate is active before calling
-	fn update_active(candidate: T::AccountId, total: BalanceOf<T>) {
-		let mut candidates = <CandidatePool<T>>::get();
-		candidates.remove(&Bond::from_owner(candidate.clone()));
-		candidates.insert(Bond {
-			owner: candidate,
-			amount: total,
-		});
-		<CandidatePool<T>>::put(candidates);
-	}
-	// Calculate round issuance based on total staked for the given round
-	fn compute_issuance(staked: BalanceOf<T>) -> BalanceOf<T> {
-		let config = <InflationConfig<T>>::get();
-		let round_issuance = inflation::round_issuance_range::<T>(config.round);
-		if staked < config.expect.min {
-			return round_issuance.min;
-		} else if staked > config.expect.max {
-			return round_issuance.max;
-		} else {
-			// TODO: split up into 3 branches
-			// 1. min < staked < ideal
-			// 2. ideal < staked < max
-			// 3. staked == ideal
-			return round_issuance.ideal;
+		/// Set where the rewards paid to the caller, as a validator candidate or as a nominator,
+		/// are paid
+		#[pallet::weight(T::WeightInfo::set_reward_destination())]
+		pub fn set_reward_destination(
+			origin: OriginFor<T>,
+			destination: RewardDestination<T::AccountId>,
+		) -> DispatchResultWithPostInfo {
+			let acc = ensure_signed(origin)?;
+			ensure!(Self::is_candidate(&acc) || Self::is_nominator(&acc), Error::<T>::StakerDNE);
+			if destination == RewardDestination::Staker {
+				<Payee<T>>::remove(&acc);
+			} else {
+				<Payee<T>>::insert(&acc, destination.clone());
+			}
+			Self::deposit_event(Event::RewardDestinationSet(acc, destination));
+			Ok(().into())
 		}
-	}
-	fn nominator_joins_validator(
-		nominator: T::AccountId,
-		amount: BalanceOf<T>,
-		validator: T::AccountId,
-	) -> DispatchResult {
-		let mut state = <Candidates<T>>::get(&validator).ok_or(Error::<T>::CandidateDNE)?;
-		let nomination = Bond {
-			owner: nominator.clone(),
-			amount,
-		};
-		ensure!(
-			state.nominators.insert(nomination),
-			Error::<T>::NominatorExists
-		);
-		ensure!(
-			(state.nominators.0.len() as u32) <= T::MaxNominatorsPerValidator::get(),
-			Error::<T>::TooManyNominators
-		);
-		T::Currency::reserve(&nominator, amount)?;
-		let new_total = state.total + amount;
-		if state.is_active() {
-			Self::update_active(validator.clone(), new_total);
-		}
-		let new_total_locked = <Total<T>>::get() + amount;
-		<Total<T>>::put(new_total_locked);
-		state.total = new_total;
-		<Candidates<T>>::insert(&validator, state);
-		Self::deposit_event(RawEvent::ValidatorNominated(
-			nominator, amount, validator, new_total,
-		));
-		Ok(())
-	}
-	fn nominator_revokes_validator(acc: T::AccountId, validator: T::AccountId) -> DispatchResult {
-		let mut nominator = <Nominators<T>>::get(&acc).ok_or(Error::<T>::NominatorDNE)?;
-		let remaining = nominator
-			.rm_nomination(validator.clone())
-			.ok_or(Error::<T>::NominationDNE)?;
-		ensure!(
-			remaining >= T::MinNominatorStk::get(),
-			Error::<T>::NomBondBelowMin
-		);
-		Self::nominator_leaves_validator(acc.clone(), validator)?;
-		<Nominators<T>>::insert(&acc, nominator);
-		Ok(())
-	}
-	fn nominator_leaves_validator(
-		nominator: T::AccountId,
-		validator: T::AccountId,
-	) -> DispatchResult {
-		let mut state = <Candidates<T>>::get(&validator).ok_or(Error::<T>::CandidateDNE)?;
-		let mut exists: Option<BalanceOf<T>> = None;
-		let noms = state
-			.nominators
-			.0
-			.into_iter()
-			.filter_map(|nom| {
-				if nom.owner != nominator {
-					Some(nom)
-				} else {
-					exists = Some(nom.amount);
-					None
+		/// Pay out the rewards of `validator` and its nominators for `round`. The rewards of a
+		/// round can be claimed by any account once `BondDuration` rounds have started since, and
+		/// they expire `RewardClaimWindow` rounds later.
+		#[pallet::weight(T::WeightInfo::claim_rewards(T::MaxNominatorsPerValidator::get()))]
+		pub fn claim_rewards(
+			origin: OriginFor<T>,
+			round: RoundIndex,
+			validator: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let issuance = <RoundIssuance<T>>::get(round).ok_or(Error::<T>::RoundNotClaimable)?;
+			ensure!(<AwardedPts<T>>::contains_key(round, &validator), Error::<T>::RewardsDNE);
+			let pts = <AwardedPts<T>>::take(round, &validator);
+			let pct_due = Perbill::from_rational_approximation(pts, <Points<T>>::get(round));
+			let amt_due = pct_due * issuance;
+			if amt_due <= T::Currency::minimum_balance() {
+				return Ok(().into());
+			}
+			// the snapshot is kept until the rewards expire so the round remains slashable
+			let state = <AtStake<T>>::get(round, &validator);
+			for (owner, due) in Self::split_reward(validator.clone(), amt_due, state) {
+				if due > T::Currency::minimum_balance() {
+					Self::pay_reward(owner, validator.clone(), due);
 				}
-			})
-			.collect();
-		let nominators = OrderedSet::from(noms);
-		let nominator_stake = exists.ok_or(Error::<T>::NominatorDNE)?;
-		T::Currency::unreserve(&nominator, nominator_stake);
-		state.nominators = nominators;
-		state.total -= nominator_stake;
-		if state.is_active() {
-			Self::update_active(validator.clone(), state.total);
+			}
+			Ok(().into())
+		}
+		/// Report an offence by `validator` in `round`. The slash is computed from the exposure in
+		/// `AtStake` so it must be reported before the rewards of the round expire.
+		#[pallet::weight(T::WeightInfo::report_offence(T::MaxNominatorsPerValidator::get()))]
+		pub fn report_offence(
+			origin: OriginFor<T>,
+			validator: T::AccountId,
+			round: RoundIndex,
+		) -> DispatchResultWithPostInfo {
+			T::ReportOrigin::ensure_origin(origin)?;
+			Self::report_offence_by(validator, round)?;
+			Ok(().into())
+		}
+		/// Cancel deferred slashes scheduled for `round` by their index in `UnappliedSlashes`
+		#[pallet::weight(T::WeightInfo::cancel_deferred_slash(slash_indices.len() as u32))]
+		pub fn cancel_deferred_slash(
+			origin: OriginFor<T>,
+			round: RoundIndex,
+			slash_indices: Vec<u32>,
+		) -> DispatchResultWithPostInfo {
+			T::SlashCancelOrigin::ensure_origin(origin)?;
+			ensure!(!slash_indices.is_empty(), Error::<T>::EmptySlashIndices);
+			let mut indices = slash_indices;
+			indices.sort_unstable();
+			indices.dedup();
+			let mut unapplied = <UnappliedSlashes<T>>::get(round);
+			ensure!(
+				(indices[indices.len() - 1] as usize) < unapplied.len(),
+				Error::<T>::InvalidSlashIndex
+			);
+			for index in indices.into_iter().rev() {
+				let slash = unapplied.remove(index as usize);
+				Self::deposit_event(Event::SlashCancelled(round, slash.validator));
+			}
+			<UnappliedSlashes<T>>::insert(round, unapplied);
+			Ok(().into())
 		}
-		let new_total_locked = <Total<T>>::get() - nominator_stake;
-		<Total<T>>::put(new_total_locked);
-		let new_total = state.total;
-		<Candidates<T>>::insert(&validator, state);
-		Self::deposit_event(RawEvent::NominatorLeftValidator(
-			nominator,
-			validator,
-			nominator_stake,
-			new_total,
-		));
-		Ok(())
 	}
-	fn pay_stakers(next: RoundIndex) {
-		let mint = |amt: BalanceOf<T>, to: T::AccountId| {
-			if amt > T::Currency::minimum_balance() {
-				if let Ok(imb) = T::Currency::deposit_into_existing(&to, amt) {
-					Self::deposit_event(RawEvent::Rewarded(to.clone(), imb.peek()));
-				}
+
+	impl<T: Config> Pallet<T> {
+		/// Number of blocks left until the round changes, including the block that changes it
+		pub fn blocks_left_in_round() -> u32 {
+			<Round<T>>::get().blocks_left(<frame_system::Module<T>>::block_number())
+		}
+		/// Rewards that `account` can claim for `round` as a validator or nominator. The issuance
+		/// is computed from the current total issuance so this is an estimate until the round
+		/// becomes claimable, and it is zero for validators that were claimed for or once the
+		/// rewards expire.
+		pub fn pending_rewards(round: RoundIndex, account: &T::AccountId) -> BalanceOf<T> {
+			let total = <Points<T>>::get(round);
+			if total.is_zero() || Self::rewards_expired(round) {
+				return BalanceOf::<T>::zero();
 			}
-		};
-		let duration = T::BondDuration::get();
-		if next > duration {
-			let round_to_payout = next - duration;
-			let total = <Points>::get(round_to_payout);
-			let total_staked = <Staked<T>>::get(round_to_payout);
-			let issuance = Self::compute_issuance(total_staked);
-			for (val, pts) in <AwardedPts<T>>::drain_prefix(round_to_payout) {
-				let pct_due = Perbill::from_rational_approximation(pts, total);
-				let mut amt_due = pct_due * issuance;
+			let issuance = <RoundIssuance<T>>::get(round).unwrap_or_else(|| {
+				let issuance = Self::compute_issuance(round);
+				issuance - <ParachainBondInfo<T>>::get().percent * issuance
+			});
+			let mut pending = BalanceOf::<T>::zero();
+			for (val, pts) in <AwardedPts<T>>::iter_prefix(round) {
+				let amt_due = Perbill::from_rational_approximation(pts, total) * issuance;
 				if amt_due <= T::Currency::minimum_balance() {
 					continue;
 				}
-				// Take the snapshot of block author and nominations
-				let state = <AtStake<T>>::take(round_to_payout, &val);
-				if state.nominators.is_empty() {
-					// solo validator with no nominators
-					mint(amt_due, val.clone());
+				let state = <AtStake<T>>::get(round, &val);
+				for (owner, due) in Self::split_reward(val, amt_due, state) {
+					// rewards below the existential deposit are not minted
+					if &owner == account && due > T::Currency::minimum_balance() {
+						pending += due;
+					}
+				}
+			}
+			pending
+		}
+		/// Smallest nomination that `validator` accepts from an account that does not nominate it
+		/// yet. Once the validator has `max_nominators_per_validator` nominations, a new
+		/// nomination must be larger than the lowest one, which it evicts. New nominators must also
+		/// stake at least `min_nominator_stk`.
+		pub fn min_nomination_to_enter(validator: &T::AccountId) -> Option<BalanceOf<T>> {
+			let state = <Candidates<T>>::get(validator)?;
+			let min = T::MinNomination::get();
+			let max_nominators = <Params<T>>::get().max_nominators_per_validator;
+			if (state.nominators.0.len() as u32) < max_nominators {
+				return Some(min);
+			}
+			state
+				.lowest_nomination()
+				.map(|lowest| lowest.amount.saturating_add(1u32.into()).max(min))
+		}
+		/// Whether the staking parameters are within the bounds set by the runtime
+		pub fn staking_params_in_bounds(params: &StakingParams<BalanceOf<T>>) -> bool {
+			params.blocks_per_round > 0
+				&& params.max_validators > 0
+				&& params.max_validators <= T::MaxValidators::get()
+				&& params.max_nominators_per_validator > 0
+				&& params.max_nominators_per_validator <= T::MaxNominatorsPerValidator::get()
+				&& params.min_nominator_stk >= T::MinNomination::get()
+				&& params.min_validator_stk >= params.min_nominator_stk
+		}
+		/// Check that the stake recorded in storage is consistent: `Total`, the totals of every
+		/// `Validator` and `Nominator`, both sides of every nomination, the `CandidatePool` and
+		/// its index by stake, the reserved balances, the controllers and author keys and the
+		/// `Validators` set. Called from the
+		/// tests after every operation and by the `check-stake` node subcommand.
+		pub fn do_try_state() -> Result<(), &'static str> {
+			let mut total = BalanceOf::<T>::zero();
+			// stake that each account must have reserved
+			let mut bonded: BTreeMap<T::AccountId, BalanceOf<T>> = BTreeMap::new();
+			for (account, state) in <Candidates<T>>::iter() {
+				ensure!(state.id == account, "candidate state must be keyed by its id");
+				let mut nominated = BalanceOf::<T>::zero();
+				for Bond { owner, amount } in state.nominators.0.iter() {
+					let nominator =
+						<Nominators<T>>::get(owner).ok_or("nominators of a candidate must exist")?;
+					ensure!(
+						nominator
+							.nominations
+							.0
+							.iter()
+							.any(|x| x.owner == account && x.amount == *amount),
+						"nominations must be recorded by the candidate and the nominator"
+					);
+					nominated += *amount;
+				}
+				ensure!(
+					state.total == state.bond + nominated,
+					"candidate total must be its bond plus its nominations"
+				);
+				match <CandidatePool<T>>::get(&account) {
+					Some(pooled) => {
+						ensure!(state.is_active(), "only active candidates can be in the pool");
+						ensure!(pooled == state.total, "pool must hold the total of the candidate");
+					}
+					None => ensure!(!state.is_active(), "active candidates must be in the pool"),
+				}
+				*bonded.entry(account).or_insert_with(Zero::zero) += state.bond;
+				total += state.total;
+			}
+			ensure!(
+				total == <Total<T>>::get(),
+				"total locked must be the sum of the candidate totals"
+			);
+			for (account, state) in <Nominators<T>>::iter() {
+				let mut nominated = BalanceOf::<T>::zero();
+				for Bond { owner, amount } in state.nominations.0.iter() {
+					let candidate =
+						<Candidates<T>>::get(owner).ok_or("nominations must be for candidates")?;
+					ensure!(
+						candidate
+							.nominators
+							.0
+							.iter()
+							.any(|x| x.owner == account && x.amount == *amount),
+						"nominations must be recorded by the candidate and the nominator"
+					);
+					nominated += *amount;
+				}
+				ensure!(
+					state.total == nominated,
+					"nominator total must be the sum of its nominations"
+				);
+				*bonded.entry(account).or_insert_with(Zero::zero) += state.total;
+			}
+			for (account, chunks) in <Unbonding<T>>::iter() {
+				let unbonding = chunks
+					.iter()
+					.fold(BalanceOf::<T>::zero(), |sum, chunk| sum + chunk.amount);
+				*bonded.entry(account).or_insert_with(Zero::zero) += unbonding;
+			}
+			for (account, metadata) in <Metadata<T>>::iter() {
+				ensure!(
+					<Candidates<T>>::contains_key(&account),
+					"only candidates can publish metadata"
+				);
+				*bonded.entry(account).or_insert_with(Zero::zero) += metadata.deposit;
+			}
+			for (stash, controller) in <Controllers<T>>::iter() {
+				ensure!(
+					<Candidates<T>>::contains_key(&stash),
+					"only candidates can designate a controller"
+				);
+				ensure!(
+					<Stashes<T>>::get(&controller).as_ref() == Some(&stash),
+					"controllers must be recorded by the stash and the controller"
+				);
+			}
+			ensure!(
+				<Stashes<T>>::iter().count() == <Controllers<T>>::iter().count(),
+				"controllers must be recorded by the stash and the controller"
+			);
+			for (stash, author) in <AuthorKeys<T>>::iter() {
+				ensure!(
+					<Candidates<T>>::contains_key(&stash),
+					"only candidates can set an author key"
+				);
+				ensure!(
+					<AuthorOf<T>>::get(&author).as_ref() == Some(&stash),
+					"author keys must be recorded by the stash and the author key"
+				);
+			}
+			ensure!(
+				<AuthorOf<T>>::iter().count() == <AuthorKeys<T>>::iter().count(),
+				"author keys must be recorded by the stash and the author key"
+			);
+			for (account, amount) in bonded {
+				ensure!(
+					T::Currency::reserved_balance(&account) >= amount,
+					"bonded and unbonding stake and metadata deposits must be reserved"
+				);
+			}
+			let mut pooled = 0usize;
+			for (account, amount) in <CandidatePool<T>>::iter() {
+				ensure!(
+					<Candidates<T>>::contains_key(&account),
+					"members of the pool must be candidates"
+				);
+				ensure!(
+					<CandidatesByStake<T>>::contains_key(Self::stake_key(amount), &account),
+					"members of the pool must be indexed by their stake"
+				);
+				pooled += 1;
+			}
+			ensure!(
+				<CandidatesByStake<T>>::iter().count() == pooled,
+				"only members of the pool can be indexed by their stake"
+			);
+			let validators = <Validators<T>>::get();
+			ensure!(
+				validators.windows(2).all(|pair| pair[0] < pair[1]),
+				"validators must be sorted without duplicates"
+			);
+			for validator in validators.iter() {
+				let state = <Candidates<T>>::get(validator).ok_or("validators must be candidates")?;
+				// validators that go offline or leave remain validators until the end of the round
+				ensure!(
+					!state.is_active() || <CandidatePool<T>>::contains_key(validator),
+					"active validators must be in the pool"
+				);
+			}
+			Ok(())
+		}
+		pub fn is_nominator(acc: &T::AccountId) -> bool {
+			<Nominators<T>>::get(acc).is_some()
+		}
+		pub fn is_candidate(acc: &T::AccountId) -> bool {
+			<Candidates<T>>::get(acc).is_some()
+		}
+		pub fn is_validator(acc: &T::AccountId) -> bool {
+			<Validators<T>>::get().binary_search(acc).is_ok()
+		}
+		/// Stash of the validator candidate that claims blocks with `author`, either the stash that
+		/// set `author` as its author key or `author` itself if it set no author key
+		pub fn stash_of_author(author: &T::AccountId) -> Option<T::AccountId> {
+			match <AuthorOf<T>>::get(author) {
+				Some(stash) => Some(stash),
+				None if <AuthorKeys<T>>::contains_key(author) => None,
+				None => Some(author.clone()),
+			}
+		}
+		/// Record that `authors` were eligible to author the current block, called by the author
+		/// filter once per block
+		pub fn note_eligible(authors: &[T::AccountId]) {
+			let now = <Round<T>>::get().current;
+			for author in authors {
+				<Uptime<T>>::mutate(now, author, |uptime| uptime.eligible += 1);
+			}
+		}
+		// Key of `CandidatesByStake` such that candidates with more stake are iterated first
+		fn stake_key(amount: BalanceOf<T>) -> [u8; 16] {
+			let amount: u128 = amount.unique_saturated_into();
+			(u128::max_value() - amount).to_be_bytes()
+		}
+		// ensure candidate is active before calling
+		pub(crate) fn update_active(candidate: T::AccountId, total: BalanceOf<T>) {
+			if let Some(old) = <CandidatePool<T>>::get(&candidate) {
+				<CandidatesByStake<T>>::remove(Self::stake_key(old), &candidate);
+			}
+			<CandidatesByStake<T>>::insert(Self::stake_key(total), &candidate, ());
+			<CandidatePool<T>>::insert(&candidate, total);
+		}
+		fn remove_from_pool(candidate: &T::AccountId) {
+			if let Some(old) = <CandidatePool<T>>::take(candidate) {
+				<CandidatesByStake<T>>::remove(Self::stake_key(old), candidate);
+			}
+		}
+		// Calculate round issuance based on total staked and round inflation snapshot for the
+		// given round
+		fn compute_issuance(round: RoundIndex) -> BalanceOf<T> {
+			let config = <InflationConfig<T>>::get();
+			let expect = inflation::expected_stake_range::<T>(&config.expect);
+			// rounds that started before the snapshots were taken use the current inflation
+			let inflation = <RoundInflation<T>>::get(round).unwrap_or(config.round);
+			let round_issuance = inflation::round_issuance_range::<T>(inflation);
+			inflation::issuance_for(
+				<Staked<T>>::get(round),
+				&expect,
+				&round_issuance,
+				<Curve<T>>::get(),
+			)
+		}
+		fn nominator_joins_validator(
+			nominator: T::AccountId,
+			amount: BalanceOf<T>,
+			validator: T::AccountId,
+		) -> DispatchResult {
+			let mut state = <Candidates<T>>::get(&validator).ok_or(Error::<T>::CandidateDNE)?;
+			ensure!(
+				!state.nominators.0.iter().any(|x| x.owner == nominator),
+				Error::<T>::NominatorExists
+			);
+			let evicted = Self::nomination_to_evict(&state, amount)?;
+			T::Currency::reserve(&nominator, amount)?;
+			if let Some(evicted) = evicted {
+				Self::evict_nomination(&mut state, &validator, evicted);
+			}
+			state.nominators.insert(Bond {
+				owner: nominator.clone(),
+				amount,
+			});
+			let new_total = state.total + amount;
+			if state.is_active() {
+				Self::update_active(validator.clone(), new_total);
+			}
+			let new_total_locked = <Total<T>>::get() + amount;
+			<Total<T>>::put(new_total_locked);
+			state.total = new_total;
+			<Candidates<T>>::insert(&validator, state);
+			Self::deposit_event(Event::ValidatorNominated(
+				nominator, amount, validator, new_total,
+			));
+			Ok(())
+		}
+		/// The nomination that a new nomination of `amount` to the candidate `state` evicts if the
+		/// candidate has `max_nominators_per_validator` nominations. Fails if the new nomination is
+		/// not larger than the lowest nomination.
+		fn nomination_to_evict(
+			state: &Candidate<T>,
+			amount: BalanceOf<T>,
+		) -> Result<Option<Bond<T::AccountId, BalanceOf<T>>>, DispatchError> {
+			let max_nominators = <Params<T>>::get().max_nominators_per_validator;
+			if (state.nominators.0.len() as u32) < max_nominators {
+				return Ok(None);
+			}
+			let lowest = state
+				.lowest_nomination()
+				.ok_or(Error::<T>::TooManyNominators)?;
+			ensure!(amount > lowest.amount, Error::<T>::TooManyNominators);
+			Ok(Some(lowest.clone()))
+		}
+		/// Remove the nomination `evicted` from the candidate `state` and from its nominator, which
+		/// leaves the set of nominators if it was its only nomination. The stake is unbonded.
+		fn evict_nomination(
+			state: &mut Candidate<T>,
+			validator: &T::AccountId,
+			evicted: Bond<T::AccountId, BalanceOf<T>>,
+		) {
+			let Bond { owner, amount } = evicted;
+			state.rm_nominator(owner.clone());
+			<Total<T>>::mutate(|total| *total -= amount);
+			Self::deposit_event(Event::NominationEvicted(owner.clone(), validator.clone(), amount));
+			if let Some(mut nominator) = <Nominators<T>>::get(&owner) {
+				nominator.rm_nomination(validator.clone());
+				if nominator.nominations.0.is_empty() {
+					<Nominators<T>>::remove(&owner);
+					<AutoCompound<T>>::remove(&owner);
+					<Payee<T>>::remove(&owner);
+					Self::deposit_event(Event::NominatorLeft(owner.clone(), amount));
 				} else {
-					// pay validator first; commission + due_portion
-					let val_pct = Perbill::from_rational_approximation(state.bond, state.total);
-					let commission = state.fee * amt_due;
-					let val_due = if commission > T::Currency::minimum_balance() {
-						amt_due -= commission;
-						(val_pct * amt_due) + commission
+					<Nominators<T>>::insert(&owner, nominator);
+				}
+			}
+			Self::schedule_unbond(owner, validator.clone(), amount);
+		}
+		fn nominator_revokes_validator(
+			acc: T::AccountId,
+			validator: T::AccountId,
+		) -> DispatchResult {
+			let mut nominator = <Nominators<T>>::get(&acc).ok_or(Error::<T>::NominatorDNE)?;
+			let remaining = nominator
+				.rm_nomination(validator.clone())
+				.ok_or(Error::<T>::NominationDNE)?;
+			ensure!(
+				remaining >= <Params<T>>::get().min_nominator_stk,
+				Error::<T>::NomBondBelowMin
+			);
+			Self::nominator_leaves_validator(acc.clone(), validator)?;
+			<Nominators<T>>::insert(&acc, nominator);
+			Ok(())
+		}
+		fn nominator_leaves_validator(
+			nominator: T::AccountId,
+			validator: T::AccountId,
+		) -> DispatchResult {
+			let mut state = <Candidates<T>>::get(&validator).ok_or(Error::<T>::CandidateDNE)?;
+			let mut exists: Option<BalanceOf<T>> = None;
+			let noms = state
+				.nominators
+				.0
+				.into_iter()
+				.filter_map(|nom| {
+					if nom.owner != nominator {
+						Some(nom)
 					} else {
-						// commission is negligible so not applied
-						val_pct * amt_due
-					};
-					mint(val_due, val.clone());
-					// pay nominators due portion
-					for Bond { owner, amount } in state.nominators {
-						let percent = Perbill::from_rational_approximation(amount, state.total);
-						let due = percent * amt_due;
-						mint(due, owner);
+						exists = Some(nom.amount);
+						None
 					}
-				}
+				})
+				.collect();
+			let nominators = OrderedSet::from(noms);
+			let nominator_stake = exists.ok_or(Error::<T>::NominatorDNE)?;
+			state.nominators = nominators;
+			state.total -= nominator_stake;
+			if state.is_active() {
+				Self::update_active(validator.clone(), state.total);
 			}
+			let new_total_locked = <Total<T>>::get() - nominator_stake;
+			<Total<T>>::put(new_total_locked);
+			let new_total = state.total;
+			<Candidates<T>>::insert(&validator, state);
+			Self::deposit_event(Event::NominatorLeftValidator(
+				nominator.clone(),
+				validator.clone(),
+				nominator_stake,
+				new_total,
+			));
+			Self::schedule_unbond(nominator, validator, nominator_stake);
+			Ok(())
 		}
-	}
-	fn execute_delayed_validator_exits(next: RoundIndex) {
-		let remain_exits = <ExitQueue<T>>::get()
-			.0
-			.into_iter()
-			.filter_map(|x| {
-				if x.amount > next {
-					Some(x)
+		/// Queue nominated stake to be unreserved after `UnbondingDelay` rounds
+		fn schedule_unbond(nominator: T::AccountId, validator: T::AccountId, amount: BalanceOf<T>) {
+			let delay = T::UnbondingDelay::get();
+			if delay.is_zero() {
+				T::Currency::unreserve(&nominator, amount);
+				Self::deposit_event(Event::Unbonded(nominator, amount));
+				return;
+			}
+			let when = <Round<T>>::get().current + delay;
+			<Unbonding<T>>::mutate(&nominator, |chunks| {
+				chunks.push(UnbondingChunk {
+					validator: validator.clone(),
+					amount,
+					when,
+				})
+			});
+			<UnbondingQueue<T>>::mutate(when, |queue| {
+				if !queue.contains(&nominator) {
+					queue.push(nominator.clone());
+				}
+			});
+			Self::deposit_event(Event::NominationUnbonding(nominator, validator, amount, when));
+		}
+		fn execute_delayed_unbonds(next: RoundIndex) {
+			for nominator in <UnbondingQueue<T>>::take(next) {
+				let mut unbonded = BalanceOf::<T>::zero();
+				let remaining = <Unbonding<T>>::get(&nominator)
+					.into_iter()
+					.filter_map(|x| {
+						if x.when > next {
+							Some(x)
+						} else {
+							unbonded += x.amount;
+							None
+						}
+					})
+					.collect::<Vec<UnbondingChunk<T::AccountId, BalanceOf<T>>>>();
+				if remaining.is_empty() {
+					<Unbonding<T>>::remove(&nominator);
 				} else {
-					if let Some(state) = <Candidates<T>>::get(&x.owner) {
-						for bond in state.nominators.0 {
-							// return stake to nominator
-							T::Currency::unreserve(&bond.owner, bond.amount);
-							// remove nomination from nominator state
-							if let Some(mut nominator) = <Nominators<T>>::get(&bond.owner) {
-								if let Some(remaining) = nominator.rm_nomination(x.owner.clone()) {
-									if remaining.is_zero() {
-										<Nominators<T>>::remove(&bond.owner);
-									} else {
-										<Nominators<T>>::insert(&bond.owner, nominator);
+					<Unbonding<T>>::insert(&nominator, remaining);
+				}
+				T::Currency::unreserve(&nominator, unbonded);
+				Self::deposit_event(Event::Unbonded(nominator, unbonded));
+			}
+		}
+		/// Compute the slash for an offence by `validator` in `round` and apply it, or defer it by
+		/// `SlashDeferDuration` rounds
+		pub fn report_offence_by(validator: T::AccountId, round: RoundIndex) -> DispatchResult {
+			ensure!(
+				!Self::rewards_expired(round) && <AtStake<T>>::contains_key(round, &validator),
+				Error::<T>::NoSnapshotForOffence
+			);
+			ensure!(
+				!<Offenders<T>>::get(round, &validator),
+				Error::<T>::OffenceAlreadyReported
+			);
+			let fraction = T::SlashFraction::get();
+			let exposure = <AtStake<T>>::get(round, &validator);
+			let slash = UnappliedSlash {
+				validator: validator.clone(),
+				own: fraction * exposure.bond,
+				others: exposure
+					.nominators
+					.into_iter()
+					.map(|x| Bond {
+						owner: x.owner,
+						amount: fraction * x.amount,
+					})
+					.collect(),
+			};
+			<Offenders<T>>::insert(round, &validator, true);
+			Self::deposit_event(Event::OffenceReported(round, validator.clone(), fraction));
+			let defer = T::SlashDeferDuration::get();
+			if defer.is_zero() {
+				Self::apply_slash(slash);
+			} else {
+				let when = <Round<T>>::get().current + defer;
+				<UnappliedSlashes<T>>::mutate(when, |slashes| slashes.push(slash));
+				Self::deposit_event(Event::SlashDeferred(when, validator));
+			}
+			Ok(())
+		}
+		fn apply_deferred_slashes(next: RoundIndex) {
+			for slash in <UnappliedSlashes<T>>::take(next) {
+				Self::apply_slash(slash);
+			}
+		}
+		fn apply_slash(slash: UnappliedSlash<T::AccountId, BalanceOf<T>>) {
+			let validator = slash.validator;
+			// the validator may have exited since the offence, its nominators are then slashed
+			// while their stake is unbonding
+			let mut candidate = <Candidates<T>>::get(&validator);
+			let before = candidate
+				.as_ref()
+				.map(|state| state.total)
+				.unwrap_or_else(Zero::zero);
+			if let Some(state) = candidate.as_mut() {
+				let own = state.slash_bond(slash.own);
+				Self::slash_reserved(&validator, own);
+			}
+			for Bond { owner, amount } in slash.others {
+				let mut slashed = BalanceOf::<T>::zero();
+				if let (Some(state), Some(mut nominator)) =
+					(candidate.as_mut(), <Nominators<T>>::get(&owner))
+				{
+					if let Some(amt) = nominator.slash_nomination(validator.clone(), amount) {
+						state.slash_nominator(owner.clone(), amt);
+						<Nominators<T>>::insert(&owner, nominator);
+						slashed = amt;
+					}
+				}
+				// stake that left the validator since the offence is slashed while unbonding
+				let unbonding = Self::slash_unbonding(&owner, &validator, amount - slashed);
+				Self::slash_reserved(&owner, slashed + unbonding);
+			}
+			if let Some(state) = candidate {
+				<Total<T>>::mutate(|total| *total -= before - state.total);
+				if state.is_active() {
+					Self::update_active(validator.clone(), state.total);
+				}
+				<Candidates<T>>::insert(&validator, state);
+			}
+		}
+		// Slash at most `amount` off the stake unbonding from `validator`, returns the amount
+		// slashed
+		fn slash_unbonding(
+			nominator: &T::AccountId,
+			validator: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> BalanceOf<T> {
+			if amount.is_zero() || !<Unbonding<T>>::contains_key(nominator) {
+				return BalanceOf::<T>::zero();
+			}
+			let mut remaining = amount;
+			let mut chunks = <Unbonding<T>>::get(nominator);
+			for chunk in chunks.iter_mut().filter(|x| &x.validator == validator) {
+				let slashed = remaining.min(chunk.amount);
+				chunk.amount -= slashed;
+				remaining -= slashed;
+			}
+			<Unbonding<T>>::insert(nominator, chunks);
+			amount - remaining
+		}
+		fn slash_reserved(who: &T::AccountId, amount: BalanceOf<T>) {
+			if amount.is_zero() {
+				return;
+			}
+			let (imbalance, _) = T::Currency::slash_reserved(who, amount);
+			Self::deposit_event(Event::Slashed(who.clone(), imbalance.peek()));
+			T::Slash::on_unbalanced(imbalance);
+		}
+		/// Fix the issuance of the round to pay out so that its rewards can be claimed with
+		/// `claim_rewards`, and remove the rewards that were not claimed within `RewardClaimWindow`
+		fn pay_stakers(next: RoundIndex) {
+			let duration = T::BondDuration::get();
+			if next > duration {
+				let round_to_payout = next - duration;
+				if !<Points<T>>::get(round_to_payout).is_zero() {
+					let mut issuance = Self::compute_issuance(round_to_payout);
+					// mint the parachain bond reserve before stakers are paid
+					let bond = <ParachainBondInfo<T>>::get();
+					let reserve = bond.percent * issuance;
+					if !reserve.is_zero() {
+						let imb = T::Currency::deposit_creating(&bond.account, reserve);
+						issuance -= imb.peek();
+						if !imb.peek().is_zero() {
+							Self::deposit_event(Event::ReservedForParachainBond(
+								round_to_payout,
+								bond.account,
+								imb.peek(),
+							));
+						}
+					}
+					<RoundIssuance<T>>::insert(round_to_payout, issuance);
+				}
+				let window = T::RewardClaimWindow::get();
+				if round_to_payout > window {
+					Self::expire_rewards(round_to_payout - window);
+				}
+			}
+		}
+		// the rest of the history of the round is removed by `fn prune_history`
+		fn expire_rewards(round: RoundIndex) {
+			<RoundIssuance<T>>::remove(round);
+		}
+		/// Whether the rewards of `round` expired, offences in `round` can no longer be reported
+		fn rewards_expired(round: RoundIndex) -> bool {
+			let window = T::BondDuration::get() + T::RewardClaimWindow::get();
+			round.saturating_add(window) <= <Round<T>>::get().current
+		}
+		/// Remove at most `MaxPrunedPerBlock` entries of the history of the rounds that ended
+		/// more than `HistoryDepth` rounds ago, starting from `OldestRound`
+		pub(crate) fn prune_history() -> Weight {
+			let now = <Round<T>>::get().current;
+			let budget = T::MaxPrunedPerBlock::get() as usize;
+			let mut oldest = <OldestRound<T>>::get();
+			let mut removed = 0usize;
+			while oldest.saturating_add(T::HistoryDepth::get()) < now && removed < budget {
+				removed += <AtStake<T>>::drain_prefix(oldest)
+					.take(budget - removed)
+					.count();
+				removed += <AwardedPts<T>>::drain_prefix(oldest)
+					.take(budget - removed)
+					.count();
+				removed += <Uptime<T>>::drain_prefix(oldest)
+					.take(budget - removed)
+					.count();
+				removed += <Offenders<T>>::drain_prefix(oldest)
+					.take(budget - removed)
+					.count();
+				if removed >= budget {
+					// the round may have entries left, resume from it in the next block
+					break;
+				}
+				<Points<T>>::remove(oldest);
+				<Staked<T>>::remove(oldest);
+				<RoundInflation<T>>::remove(oldest);
+				removed += 3;
+				oldest += 1;
+			}
+			<OldestRound<T>>::put(oldest);
+			T::WeightInfo::prune_history(removed as u32)
+		}
+		/// Pay the reward `amount` due to `owner` for the points of `validator` to its
+		/// `RewardDestination`, bonding back the fraction that is compounded
+		fn pay_reward(owner: T::AccountId, validator: T::AccountId, amount: BalanceOf<T>) {
+			let (payee, fraction) = match <Payee<T>>::get(&owner) {
+				RewardDestination::Staker => (owner.clone(), <AutoCompound<T>>::get(&owner)),
+				RewardDestination::Account(payee) => (payee, <AutoCompound<T>>::get(&owner)),
+				RewardDestination::Restake => (owner.clone(), Perbill::one()),
+			};
+			let restake = fraction * amount;
+			let to_owner = if payee == owner { amount } else { restake };
+			if !to_owner.is_zero() {
+				if let Ok(imb) = T::Currency::deposit_into_existing(&owner, to_owner) {
+					Self::deposit_event(Event::Rewarded(owner.clone(), imb.peek()));
+					if !restake.is_zero() {
+						Self::compound(owner.clone(), validator, restake);
+					}
+				}
+			}
+			if payee != owner && amount > restake {
+				let imb = T::Currency::deposit_creating(&payee, amount - restake);
+				if !imb.peek().is_zero() {
+					Self::deposit_event(Event::Rewarded(payee, imb.peek()));
+				}
+			}
+		}
+		/// Bond back `amount` of the rewards paid to `owner` for the points of `validator`, unless
+		/// the validator is leaving or `owner` no longer nominates it
+		fn compound(owner: T::AccountId, validator: T::AccountId, amount: BalanceOf<T>) {
+			let mut state = match <Candidates<T>>::get(&validator) {
+				Some(state) if !state.is_leaving() => state,
+				_ => return,
+			};
+			if owner == validator {
+				if T::Currency::reserve(&owner, amount).is_err() {
+					return;
+				}
+				state.bond_more(amount);
+			} else {
+				let mut nominator = match <Nominators<T>>::get(&owner) {
+					Some(nominator) => nominator,
+					None => return,
+				};
+				if nominator.inc_nomination(validator.clone(), amount).is_none()
+					|| T::Currency::reserve(&owner, amount).is_err()
+				{
+					return;
+				}
+				state.inc_nominator(owner.clone(), amount);
+				<Nominators<T>>::insert(&owner, nominator);
+			}
+			<Total<T>>::mutate(|total| *total += amount);
+			if state.is_active() {
+				Self::update_active(validator.clone(), state.total);
+			}
+			<Candidates<T>>::insert(&validator, state);
+			Self::deposit_event(Event::Compounded(owner, validator, amount));
+		}
+		/// Split `amt_due` for the points of `validator` between the validator and its nominators
+		/// in the snapshot `state`. The validator is always first.
+		fn split_reward(
+			validator: T::AccountId,
+			mut amt_due: BalanceOf<T>,
+			state: ValidatorSnapshot<T::AccountId, BalanceOf<T>>,
+		) -> Vec<(T::AccountId, BalanceOf<T>)> {
+			if state.nominators.is_empty() {
+				// solo validator with no nominators
+				let mut rewards = Vec::with_capacity(1);
+				rewards.push((validator, amt_due));
+				return rewards;
+			}
+			let mut rewards = Vec::with_capacity(state.nominators.len() + 1);
+			// pay validator first; commission + due_portion
+			let val_pct = Perbill::from_rational_approximation(state.bond, state.total);
+			let commission = state.fee * amt_due;
+			let val_due = if commission > T::Currency::minimum_balance() {
+				amt_due -= commission;
+				(val_pct * amt_due) + commission
+			} else {
+				// commission is negligible so not applied
+				val_pct * amt_due
+			};
+			rewards.push((validator, val_due));
+			// pay nominators due portion
+			for Bond { owner, amount } in state.nominators {
+				let percent = Perbill::from_rational_approximation(amount, state.total);
+				rewards.push((owner, percent * amt_due));
+			}
+			rewards
+		}
+		/// Signer of `origin`, or the stash that it controls if it is a controller
+		fn ensure_stash(origin: OriginFor<T>) -> Result<T::AccountId, DispatchError> {
+			let who = ensure_signed(origin)?;
+			Ok(<Stashes<T>>::get(&who).unwrap_or(who))
+		}
+		/// Remove the metadata of `validator` and return its deposit, if it published any
+		fn remove_metadata(validator: &T::AccountId) -> Option<BalanceOf<T>> {
+			let deposit = <Metadata<T>>::take(validator)?.deposit;
+			T::Currency::unreserve(validator, deposit);
+			Self::deposit_event(Event::MetadataCleared(validator.clone(), deposit));
+			Some(deposit)
+		}
+		fn execute_delayed_validator_exits(next: RoundIndex) {
+			let remain_exits = <ExitQueue<T>>::get()
+				.0
+				.into_iter()
+				.filter_map(|x| {
+					if x.amount > next {
+						Some(x)
+					} else {
+						if let Some(state) = <Candidates<T>>::get(&x.owner) {
+							for bond in state.nominators.0 {
+								// unbond stake of nominator, which remains slashable meanwhile
+								Self::schedule_unbond(
+									bond.owner.clone(),
+									x.owner.clone(),
+									bond.amount,
+								);
+								// remove nomination from nominator state
+								if let Some(mut nominator) = <Nominators<T>>::get(&bond.owner) {
+									if let Some(remaining) =
+										nominator.rm_nomination(x.owner.clone())
+									{
+										if remaining.is_zero() {
+											<Nominators<T>>::remove(&bond.owner);
+											<AutoCompound<T>>::remove(&bond.owner);
+											<Payee<T>>::remove(&bond.owner);
+										} else {
+											<Nominators<T>>::insert(&bond.owner, nominator);
+										}
 									}
 								}
 							}
+							// return stake to validator
+							T::Currency::unreserve(&state.id, state.bond);
+							let new_total = <Total<T>>::get() - state.total;
+							<Total<T>>::put(new_total);
+							<Candidates<T>>::remove(&x.owner);
+							<PendingCommission<T>>::remove(&x.owner);
+							<AutoCompound<T>>::remove(&x.owner);
+							<Payee<T>>::remove(&x.owner);
+							Self::remove_metadata(&x.owner);
+							if let Some(controller) = <Controllers<T>>::take(&x.owner) {
+								<Stashes<T>>::remove(&controller);
+							}
+							if let Some(author) = <AuthorKeys<T>>::take(&x.owner) {
+								<AuthorOf<T>>::remove(&author);
+							}
+							Self::deposit_event(Event::ValidatorLeft(
+								x.owner,
+								state.total,
+								new_total,
+							));
 						}
-						// return stake to validator
-						T::Currency::unreserve(&state.id, state.bond);
-						let new_total = <Total<T>>::get() - state.total;
-						<Total<T>>::put(new_total);
-						<Candidates<T>>::remove(&x.owner);
-						Self::deposit_event(RawEvent::ValidatorLeft(
-							x.owner,
-							state.total,
-							new_total,
-						));
+						None
 					}
-					None
+				})
+				.collect::<Vec<Bond<T::AccountId, RoundIndex>>>();
+			<ExitQueue<T>>::put(OrderedSet::from(remain_exits));
+		}
+		fn apply_pending_params() {
+			if let Some(new) = <PendingParams<T>>::take() {
+				let old = <Params<T>>::get();
+				<Params<T>>::put(new.clone());
+				Self::deposit_event(Event::StakingParamsSet(old, new));
+			}
+		}
+		fn execute_delayed_commission_changes(next: RoundIndex) {
+			for validator in <ScheduledCommissions<T>>::take(next) {
+				// skip requests that were replaced by a later one
+				let fee = match <PendingCommission<T>>::get(&validator) {
+					Some((fee, when)) if when == next => fee,
+					_ => continue,
+				};
+				<PendingCommission<T>>::remove(&validator);
+				if let Some(mut state) = <Candidates<T>>::get(&validator) {
+					let old = state.fee;
+					state.fee = fee;
+					<Candidates<T>>::insert(&validator, state);
+					Self::deposit_event(Event::CommissionChanged(validator, old, fee));
 				}
-			})
-			.collect::<Vec<Bond<T::AccountId, RoundIndex>>>();
-		<ExitQueue<T>>::put(OrderedSet::from(remain_exits));
-	}
-	/// Best as in most cumulatively supported in terms of stake
-	fn best_candidates_become_validators(next: RoundIndex) -> (u32, BalanceOf<T>) {
-		let (mut all_validators, mut total) = (0u32, BalanceOf::<T>::zero());
-		let mut candidates = <CandidatePool<T>>::get().0;
-		// order candidates by stake (least to greatest so requires `rev()`)
-		candidates.sort_unstable_by(|a, b| a.amount.partial_cmp(&b.amount).unwrap());
-		let max_validators = T::MaxValidators::get() as usize;
-		// choose the top MaxValidators qualified candidates, ordered by stake
-		let mut validators = candidates
-			.into_iter()
-			.rev()
-			.take(max_validators)
-			.map(|x| x.owner)
-			.collect::<Vec<T::AccountId>>();
-		// snapshot exposure for round for weighting reward distribution
-		for account in validators.iter() {
-			let state = <Candidates<T>>::get(&account)
-				.expect("all members of CandidateQ must be candidates");
-			let amount = state.total;
-			let exposure: ValidatorSnapshot<T::AccountId, BalanceOf<T>> = state.into();
-			<AtStake<T>>::insert(next, account, exposure);
-			all_validators += 1u32;
-			total += amount;
-			Self::deposit_event(RawEvent::ValidatorChosen(next, account.clone(), amount));
-		}
-		validators.sort();
-		// insert canonical validator set
-		<Validators<T>>::put(validators);
-		(all_validators, total)
+			}
+		}
+		/// Award the points of `round` to its validators according to their `Uptime`
+		fn award_uptime_points(round: RoundIndex) {
+			let mut total: RewardPoint = 0;
+			for validator in <Validators<T>>::get() {
+				let uptime = <Uptime<T>>::get(round, &validator);
+				if uptime == UptimeInfo::default() {
+					continue;
+				}
+				let pts = uptime.points();
+				if !pts.is_zero() {
+					<AwardedPts<T>>::mutate(round, &validator, |x| *x += pts);
+					total += pts;
+				}
+				Self::deposit_event(Event::UptimeRecorded(
+					round,
+					validator,
+					uptime.eligible,
+					uptime.authored,
+					pts,
+				));
+			}
+			if !total.is_zero() {
+				<Points<T>>::mutate(round, |x| *x += total);
+			}
+		}
+		/// Count the consecutive rounds in which the validators of `round` were eligible and
+		/// authored no block, those that reach `MaxMissedRounds` are made idle until they call
+		/// `go_online`. Rounds in which a validator was never eligible are not counted.
+		fn idle_unresponsive_validators(round: RoundIndex) {
+			let max_missed = T::MaxMissedRounds::get();
+			let previous = <MissedRounds<T>>::take();
+			let mut missed_rounds = Vec::new();
+			for validator in <Validators<T>>::get() {
+				let uptime = <Uptime<T>>::get(round, &validator);
+				if uptime.authored > 0 {
+					continue;
+				}
+				let mut missed = previous
+					.iter()
+					.find(|(account, _)| account == &validator)
+					.map(|(_, missed)| *missed)
+					.unwrap_or_default();
+				if uptime.eligible > 0 {
+					missed += 1;
+				}
+				if max_missed > 0 && missed >= max_missed {
+					if let Some(mut state) = <Candidates<T>>::get(&validator) {
+						if state.is_active() {
+							state.go_offline();
+							Self::remove_from_pool(&validator);
+							<Candidates<T>>::insert(&validator, state);
+							Self::deposit_event(Event::ValidatorIdled(round, validator, missed));
+							continue;
+						}
+					}
+				}
+				if missed > 0 {
+					missed_rounds.push((validator, missed));
+				}
+			}
+			<MissedRounds<T>>::put(missed_rounds);
+		}
+		/// Best as in most cumulatively supported in terms of stake
+		pub(crate) fn best_candidates_become_validators(next: RoundIndex) -> (u32, BalanceOf<T>) {
+			let (mut all_validators, mut total) = (0u32, BalanceOf::<T>::zero());
+			let max_validators = <Params<T>>::get().max_validators as usize;
+			// choose the top MaxValidators qualified candidates, ordered by stake
+			let mut validators = <CandidatesByStake<T>>::iter()
+				.take(max_validators)
+				.map(|(_, owner, _)| owner)
+				.collect::<Vec<T::AccountId>>();
+			// snapshot exposure for round for weighting reward distribution
+			for account in validators.iter() {
+				let state = <Candidates<T>>::get(&account)
+					.expect("all members of CandidateQ must be candidates");
+				let amount = state.total;
+				let exposure: ValidatorSnapshot<T::AccountId, BalanceOf<T>> = state.into();
+				<AtStake<T>>::insert(next, account, exposure);
+				all_validators += 1u32;
+				total += amount;
+				Self::deposit_event(Event::ValidatorChosen(next, account.clone(), amount));
+			}
+			validators.sort();
+			// insert canonical validator set
+			<Validators<T>>::put(validators);
+			(all_validators, total)
+		}
 	}
-}
 
-/// Add reward points to block authors:
-/// * 20 points to the block producer for producing a block in the chain
-impl<T: Config> author_inherent::EventHandler<T::AccountId> for Module<T> {
-	fn note_author(author: T::AccountId) {
-		let now = <Round>::get();
-		let score_plus_20 = <AwardedPts<T>>::get(now, &author) + 20;
-		<AwardedPts<T>>::insert(now, author, score_plus_20);
-		<Points>::mutate(now, |x| *x += 20);
+	/// Record the blocks authored in the `Uptime` of the stash of the author key, the points are
+	/// awarded when the round ends
+	impl<T: Config> author_inherent::EventHandler<T::AccountId> for Pallet<T> {
+		fn note_author(author: T::AccountId) {
+			if let Some(stash) = Self::stash_of_author(&author) {
+				let now = <Round<T>>::get().current;
+				<Uptime<T>>::mutate(now, stash, |uptime| uptime.authored += 1);
+			}
+		}
 	}
-}
 
-impl<T: Config> author_inherent::CanAuthor<T::AccountId> for Module<T> {
-	fn can_author(account: &T::AccountId) -> bool {
-		Self::is_validator(account)
+	impl<T: Config> author_inherent::CanAuthor<T::AccountId> for Pallet<T> {
+		fn can_author(account: &T::AccountId) -> bool {
+			Self::stash_of_author(account).map_or(false, |stash| Self::is_validator(&stash))
+		}
 	}
 }
@@ -0,0 +1,284 @@
+// Copyright 2019-2020 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations for stake
+//!
+//! Every change to the encoding of a stored type adds a variant to `Releases` and a module here
+//! with `pre_migrate`, `migrate` and `post_migrate`. `migrate` runs the migrations in order from
+//! the `StorageVersion` found on chain, so a runtime can skip any number of releases.
+//! `pre_migrate` and `post_migrate` check the state before and after the migration, they are
+//! meant for tests and for dry runs of an upgrade against a live state.
+use crate::{Config, Releases, StorageVersion};
+use frame_support::{traits::Get, weights::Weight};
+
+/// Migrate the storage from the version recorded in `StorageVersion` to the latest version
+pub fn migrate<T: Config>() -> Weight {
+	let mut weight = T::DbWeight::get().reads(1);
+	if <StorageVersion<T>>::get() < Releases::V2_0_0 {
+		weight += v2::migrate::<T>();
+	}
+	if <StorageVersion<T>>::get() < Releases::V3_0_0 {
+		weight += v3::migrate::<T>();
+	}
+	if <StorageVersion<T>>::get() < Releases::V4_0_0 {
+		weight += v4::migrate::<T>();
+	}
+	weight
+}
+
+/// Record the round in which each candidate joined in `Validator` and index the `CandidatePool` by
+/// stake in `CandidatesByStake`
+pub mod v2 {
+	use crate::{
+		set::OrderedSet, BalanceOf, Bond, CandidatePool, Candidates, Config, Pallet, Releases,
+		RoundIndex, StorageVersion, Validator, ValidatorStatus,
+	};
+	use frame_support::{
+		ensure,
+		storage::{migration::storage_key_iter, unhashed, StoragePrefixedMap},
+		traits::{Get, PalletInfo},
+		weights::Weight,
+		Blake2_128Concat,
+	};
+	use parity_scale_codec::{Decode, Encode};
+	use sp_runtime::{Perbill, RuntimeDebug};
+	use sp_std::vec::Vec;
+
+	#[derive(Encode, Decode, RuntimeDebug)]
+	/// `Validator` as encoded in `Releases::V1_0_0`
+	pub struct OldValidator<AccountId, Balance> {
+		pub id: AccountId,
+		pub fee: Perbill,
+		pub bond: Balance,
+		pub nominators: OrderedSet<Bond<AccountId, Balance>>,
+		pub total: Balance,
+		pub state: ValidatorStatus,
+	}
+
+	/// `CandidatePool` as stored in `Releases::V1_0_0`, a single value holding every candidate
+	pub type OldCandidatePool<AccountId, Balance> = OrderedSet<Bond<AccountId, Balance>>;
+
+	/// Round recorded for candidates that joined before `Releases::V2_0_0`
+	pub const UNKNOWN_JOINED: RoundIndex = 0;
+
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(
+			<StorageVersion<T>>::get() == Releases::V1_0_0,
+			"stake storage version must be V1_0_0 before migrating to V2_0_0"
+		);
+		let pallet = <T as frame_system::Config>::PalletInfo::name::<Pallet<T>>()
+			.ok_or("stake must be part of the runtime")?;
+		let mut candidates = Vec::new();
+		for (account, old) in storage_key_iter::<
+			T::AccountId,
+			OldValidator<T::AccountId, BalanceOf<T>>,
+			Blake2_128Concat,
+		>(pallet.as_bytes(), b"Candidates")
+		{
+			ensure!(old.id == account, "candidate state must be keyed by its id");
+			candidates.push(account);
+		}
+		let pool = unhashed::get::<OldCandidatePool<T::AccountId, BalanceOf<T>>>(
+			&<CandidatePool<T>>::final_prefix(),
+		)
+		.unwrap_or_default();
+		for bond in pool.0.iter() {
+			ensure!(
+				candidates.contains(&bond.owner),
+				"members of the pool must be candidates"
+			);
+		}
+		Ok(())
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		let mut translated = 0u64;
+		<Candidates<T>>::translate::<OldValidator<T::AccountId, BalanceOf<T>>, _>(|_, old| {
+			translated += 1;
+			Some(Validator {
+				id: old.id,
+				fee: old.fee,
+				bond: old.bond,
+				nominators: old.nominators,
+				total: old.total,
+				state: old.state,
+				joined: UNKNOWN_JOINED,
+			})
+		});
+		// the pool is stored under the prefix of the map that replaces it
+		let pool = unhashed::take::<OldCandidatePool<T::AccountId, BalanceOf<T>>>(
+			&<CandidatePool<T>>::final_prefix(),
+		)
+		.unwrap_or_default();
+		let pooled = pool.0.len() as u64;
+		for Bond { owner, amount } in pool.0 {
+			Pallet::<T>::update_active(owner, amount);
+		}
+		<StorageVersion<T>>::put(Releases::V2_0_0);
+		T::DbWeight::get().reads_writes(translated + pooled + 1, translated + 2 * pooled + 2)
+	}
+
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(
+			<StorageVersion<T>>::get() >= Releases::V2_0_0,
+			"stake storage version must be at least V2_0_0 after migrating"
+		);
+		ensure!(
+			unhashed::get_raw(&<CandidatePool<T>>::final_prefix()).is_none(),
+			"candidate pool must no longer be stored as a single value"
+		);
+		Pallet::<T>::do_try_state()
+	}
+}
+
+/// Write the staking parameters fixed in the runtime to `Params` and record the first block and
+/// length of the current round in `Round` and the annual inflation in `InflationInfo`
+pub mod v3 {
+	use super::v4;
+	use crate::{
+		inflation, BalanceOf, Config, InflationConfig, Params, Range, Releases, Round, RoundIndex,
+		RoundInfo, StorageVersion,
+	};
+	use frame_support::{ensure, storage::unhashed, traits::Get, weights::Weight};
+	use parity_scale_codec::{Decode, Encode};
+	use sp_runtime::{traits::Saturating, Perbill, RuntimeDebug};
+
+	#[derive(Encode, Decode, RuntimeDebug)]
+	/// `InflationInfo` as encoded in `Releases::V2_0_0`
+	pub struct OldInflationInfo<Balance> {
+		pub expect: Range<Balance>,
+		pub round: Range<Perbill>,
+	}
+
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(
+			<StorageVersion<T>>::get() == Releases::V2_0_0,
+			"stake storage version must be V2_0_0 before migrating to V3_0_0"
+		);
+		ensure!(
+			<Params<T>>::exists() || T::LegacyStakingParams::get().blocks_per_round > 0,
+			"staking parameters fixed in the runtime must have a round length"
+		);
+		Ok(())
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		// the staking parameters used to be constants of the runtime
+		if !<Params<T>>::exists() {
+			<Params<T>>::put(T::LegacyStakingParams::get());
+		}
+		let length = <Params<T>>::get().blocks_per_round;
+		let now = <frame_system::Module<T>>::block_number();
+		// rounds used to start after every block that is a multiple of the round length
+		let length_in_blocks: T::BlockNumber = length.max(1).into();
+		let first_block = now - now.saturating_sub(1u32.into()) % length_in_blocks;
+		let _ = <Round<T>>::translate::<RoundIndex, _>(|current| {
+			current.map(|current| RoundInfo::new(current, first_block, length))
+		});
+		// written in the layout of `Releases::V3_0_0`, which `v4` migrates from
+		let key = <InflationConfig<T>>::hashed_key();
+		if let Some(old) = unhashed::get::<OldInflationInfo<BalanceOf<T>>>(&key) {
+			unhashed::put(
+				&key,
+				&v4::OldInflationInfo {
+					expect: old.expect,
+					annual: inflation::round_to_annual(
+						old.round.clone(),
+						length,
+						T::BlockTime::get(),
+					),
+					round: old.round,
+				},
+			);
+		}
+		<StorageVersion<T>>::put(Releases::V3_0_0);
+		T::DbWeight::get().reads_writes(5, 4)
+	}
+
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(
+			<StorageVersion<T>>::get() >= Releases::V3_0_0,
+			"stake storage version must be at least V3_0_0 after migrating"
+		);
+		ensure!(
+			<Params<T>>::get().blocks_per_round > 0,
+			"staking parameters must be set after migrating"
+		);
+		let round = <Round<T>>::get();
+		ensure!(
+			round.length == <Params<T>>::get().blocks_per_round,
+			"round length must be the round length in the staking parameters"
+		);
+		ensure!(
+			round.first_block <= <frame_system::Module<T>>::block_number(),
+			"current round must have started"
+		);
+		Ok(())
+	}
+}
+
+/// Record the staking expectations in `InflationInfo` as amounts or as fractions of the total
+/// issuance
+pub mod v4 {
+	use crate::{
+		BalanceOf, Config, Expectations, InflationConfig, InflationInfo, Range, Releases,
+		StorageVersion,
+	};
+	use frame_support::{ensure, traits::Get, weights::Weight};
+	use parity_scale_codec::{Decode, Encode};
+	use sp_runtime::{Perbill, RuntimeDebug};
+
+	#[derive(Encode, Decode, RuntimeDebug)]
+	/// `InflationInfo` as encoded in `Releases::V3_0_0`
+	pub struct OldInflationInfo<Balance> {
+		pub expect: Range<Balance>,
+		pub annual: Range<Perbill>,
+		pub round: Range<Perbill>,
+	}
+
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(
+			<StorageVersion<T>>::get() == Releases::V3_0_0,
+			"stake storage version must be V3_0_0 before migrating to V4_0_0"
+		);
+		Ok(())
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		// existing expectations are amounts staked
+		let _ = <InflationConfig<T>>::translate::<OldInflationInfo<BalanceOf<T>>, _>(|old| {
+			old.map(|old| InflationInfo {
+				expect: Expectations::Absolute(old.expect),
+				annual: old.annual,
+				round: old.round,
+			})
+		});
+		<StorageVersion<T>>::put(Releases::V4_0_0);
+		T::DbWeight::get().reads_writes(2, 2)
+	}
+
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(
+			<StorageVersion<T>>::get() >= Releases::V4_0_0,
+			"stake storage version must be at least V4_0_0 after migrating"
+		);
+		ensure!(
+			<InflationConfig<T>>::get().expect.is_valid(),
+			"staking expectations must be valid"
+		);
+		Ok(())
+	}
+}
@@ -18,7 +18,7 @@
 use crate::*;
 use frame_support::{
 	impl_outer_event, impl_outer_origin, parameter_types,
-	traits::{OnFinalize, OnInitialize},
+	traits::{GenesisBuild, OnFinalize, OnInitialize},
 	weights::Weight,
 };
 use sp_core::H256;
@@ -58,7 +58,7 @@ parameter_types! {
 	pub const AvailableBlockRatio: Perbill = Perbill::one();
 	pub const SS58Prefix: u8 = 42;
 }
-impl System for Test {
+impl frame_system::Config for Test {
 	type BaseCallFilter = ();
 	type DbWeight = ();
 	type Origin = Origin;
@@ -95,50 +95,93 @@ impl pallet_balances::Config for Test {
 	type WeightInfo = ();
 }
 parameter_types! {
-	pub const BlocksPerRound: u32 = 5;
+	pub const BlockTime: u32 = 6;
 	pub const BondDuration: u32 = 2;
+	pub const RewardClaimWindow: u32 = 2;
+	pub const HistoryDepth: u32 = 4;
+	pub const MaxPrunedPerBlock: u32 = 3;
+	pub const UnbondingDelay: u32 = 2;
 	pub const MaxValidators: u32 = 5;
 	pub const MaxNominatorsPerValidator: u32 = 4;
 	pub const MaxValidatorsPerNominator: u32 = 4;
 	pub const MaxFee: Perbill = Perbill::from_percent(50);
-	pub const MinValidatorStk: u128 = 10;
-	pub const MinNominatorStk: u128 = 5;
+	pub const CommissionChangeDelay: u32 = 2;
 	pub const MinNomination: u128 = 3;
+	pub const LegacyStakingParams: StakingParams<Balance> = StakingParams {
+		blocks_per_round: 5,
+		max_validators: 5,
+		max_nominators_per_validator: 4,
+		min_validator_stk: 10,
+		min_nominator_stk: 5,
+	};
+	pub const SlashFraction: Perbill = Perbill::from_percent(10);
+	pub const SlashDeferDuration: u32 = 1;
+	pub const MaxMissedRounds: u32 = 2;
+	pub const MetadataDeposit: u128 = 10;
+	pub const MaxMetadataFieldLength: u32 = 8;
+	pub const MaxMetadataExtraLength: u32 = 16;
 }
 impl Config for Test {
 	type Event = MetaEvent;
 	type Currency = Balances;
 	type SetMonetaryPolicyOrigin = frame_system::EnsureRoot<Self::AccountId>;
-	type BlocksPerRound = BlocksPerRound;
+	type BlockTime = BlockTime;
 	type BondDuration = BondDuration;
+	type RewardClaimWindow = RewardClaimWindow;
+	type HistoryDepth = HistoryDepth;
+	type MaxPrunedPerBlock = MaxPrunedPerBlock;
+	type UnbondingDelay = UnbondingDelay;
 	type MaxValidators = MaxValidators;
 	type MaxNominatorsPerValidator = MaxNominatorsPerValidator;
 	type MaxValidatorsPerNominator = MaxValidatorsPerNominator;
 	type MaxFee = MaxFee;
-	type MinValidatorStk = MinValidatorStk;
-	type MinNominatorStk = MinNominatorStk;
+	type CommissionChangeDelay = CommissionChangeDelay;
 	type MinNomination = MinNomination;
+	type LegacyStakingParams = LegacyStakingParams;
+	type Slash = ();
+	type ReportOrigin = frame_system::EnsureRoot<AccountId>;
+	type SlashCancelOrigin = frame_system::EnsureRoot<AccountId>;
+	type SlashFraction = SlashFraction;
+	type SlashDeferDuration = SlashDeferDuration;
+	type MaxMissedRounds = MaxMissedRounds;
+	type MetadataDeposit = MetadataDeposit;
+	type MaxMetadataFieldLength = MaxMetadataFieldLength;
+	type MaxMetadataExtraLength = MaxMetadataExtraLength;
+	type WeightInfo = ();
 }
 pub type Balances = pallet_balances::Module<Test>;
-pub type Stake = Module<Test>;
+pub type Stake = Pallet<Test>;
 pub type Sys = frame_system::Module<Test>;
 
-fn genesis(
+pub(crate) fn genesis(
 	balances: Vec<(AccountId, Balance)>,
-	stakers: Vec<(AccountId, Option<AccountId>, Balance)>,
+	stakers: Vec<(AccountId, Option<AccountId>, Balance, Option<Perbill>)>,
 ) -> sp_io::TestExternalities {
-	let expect: Range<Balance> = Range {
+	let expect: Expectations<Balance> = Expectations::Absolute(Range {
 		min: 700,
 		ideal: 700,
 		max: 700,
-	};
+	});
 	// very unrealistic test parameterization, would be dumb to have per-round inflation this high
 	let round: Range<Perbill> = Range {
 		min: Perbill::from_percent(5),
 		ideal: Perbill::from_percent(5),
 		max: Perbill::from_percent(5),
 	};
-	let inflation_config: InflationInfo<Balance> = InflationInfo { expect, round };
+	// only used to recompute the round inflation when the round length changes
+	let annual: Range<Perbill> = Perbill::from_percent(50).into();
+	let inflation_config: InflationInfo<Balance> = InflationInfo {
+		expect,
+		annual,
+		round,
+	};
+	let params: StakingParams<Balance> = StakingParams {
+		blocks_per_round: 5,
+		max_validators: 5,
+		max_nominators_per_validator: 4,
+		min_validator_stk: 10,
+		min_nominator_stk: 5,
+	};
 	let mut storage = frame_system::GenesisConfig::default()
 		.build_storage::<Test>()
 		.unwrap();
@@ -147,6 +190,7 @@ fn genesis(
 	GenesisConfig::<Test> {
 		stakers,
 		inflation_config,
+		params,
 	}
 	.assimilate_storage(&mut storage)
 	.unwrap();
@@ -155,6 +199,10 @@ fn genesis(
 	ext
 }
 
+pub(crate) fn no_stakers() -> sp_io::TestExternalities {
+	genesis(vec![], vec![])
+}
+
 pub(crate) fn two_validators_four_nominators() -> sp_io::TestExternalities {
 	genesis(
 		vec![
@@ -170,13 +218,13 @@ pub(crate) fn two_validators_four_nominators() -> sp_io::TestExternalities {
 		],
 		vec![
 			// validators
-			(1, None, 500),
-			(2, None, 200),
+			(1, None, 500, None),
+			(2, None, 200, None),
 			// nominators
-			(3, Some(1), 100),
-			(4, Some(1), 100),
-			(5, Some(2), 100),
-			(6, Some(2), 100),
+			(3, Some(1), 100, None),
+			(4, Some(1), 100, None),
+			(5, Some(2), 100, None),
+			(6, Some(2), 100, None),
 		],
 	)
 }
@@ -196,12 +244,12 @@ pub(crate) fn five_validators_no_nominators() -> sp_io::TestExternalities {
 		],
 		vec![
 			// validators
-			(1, None, 100),
-			(2, None, 90),
-			(3, None, 80),
-			(4, None, 70),
-			(5, None, 60),
-			(6, None, 50),
+			(1, None, 100, None),
+			(2, None, 90, None),
+			(3, None, 80, None),
+			(4, None, 70, None),
+			(5, None, 60, None),
+			(6, None, 50, None),
 		],
 	)
 }
@@ -222,17 +270,17 @@ pub(crate) fn five_validators_five_nominators() -> sp_io::TestExternalities {
 		],
 		vec![
 			// validators
-			(1, None, 20),
-			(2, None, 20),
-			(3, None, 20),
-			(4, None, 20),
-			(5, None, 10),
+			(1, None, 20, None),
+			(2, None, 20, None),
+			(3, None, 20, None),
+			(4, None, 20, None),
+			(5, None, 10, None),
 			// nominators
-			(6, Some(1), 10),
-			(7, Some(1), 10),
-			(8, Some(2), 10),
-			(9, Some(2), 10),
-			(10, Some(1), 10),
+			(6, Some(1), 10, None),
+			(7, Some(1), 10, None),
+			(8, Some(2), 10, None),
+			(9, Some(2), 10, None),
+			(10, Some(1), 10, None),
 		],
 	)
 }
@@ -242,10 +290,10 @@ pub(crate) fn one_validator_two_nominators() -> sp_io::TestExternalities {
 		vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100), (6, 100)],
 		vec![
 			// validators
-			(1, None, 20),
+			(1, None, 20, None),
 			// nominators
-			(2, Some(1), 10),
-			(3, Some(1), 10),
+			(2, Some(1), 10, None),
+			(3, Some(1), 10, None),
 		],
 	)
 }
@@ -259,6 +307,7 @@ pub(crate) fn roll_to(n: u64) {
 		Sys::on_initialize(Sys::block_number());
 		Balances::on_initialize(Sys::block_number());
 		Stake::on_initialize(Sys::block_number());
+		assert_eq!(Stake::do_try_state(), Ok(()));
 	}
 }
 
@@ -266,7 +315,7 @@ pub(crate) fn last_event() -> MetaEvent {
 	Sys::events().pop().expect("Event expected").event
 }
 
-pub(crate) fn events() -> Vec<RawEvent<u64, u128, u64>> {
+pub(crate) fn events() -> Vec<Event<Test>> {
 	Sys::events()
 		.into_iter()
 		.map(|r| r.event)
@@ -280,7 +329,7 @@ pub(crate) fn events() -> Vec<RawEvent<u64, u128, u64>> {
 		.collect::<Vec<_>>()
 }
 
-// Same storage changes as EventHandler::note_author impl
+// Same storage changes as awarding the uptime points of a validator at the end of a round
 pub(crate) fn set_author(round: u32, acc: u64, pts: u32) {
 	<Stake as Store>::Points::mutate(round, |p| *p += pts);
 	<Stake as Store>::AwardedPts::mutate(round, acc, |p| *p += pts);
@@ -16,10 +16,21 @@
 
 //! Unit testing
 use crate::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+	assert_noop,
+	storage::{unhashed, StoragePrefixedMap},
+};
 use mock::*;
 use sp_runtime::DispatchError;
 
+/// `assert_ok!` that also checks the invariants of the stake pallet state
+macro_rules! assert_ok {
+	( $x:expr $(,)? ) => {
+		frame_support::assert_ok!($x);
+		frame_support::assert_ok!(Stake::do_try_state());
+	};
+}
+
 #[test]
 fn geneses() {
 	two_validators_four_nominators().execute_with(|| {
@@ -68,6 +79,43 @@ fn geneses() {
 	});
 }
 
+#[test]
+fn genesis_sets_commission_and_multiple_nominations() {
+	genesis(
+		vec![(1, 100), (2, 100), (3, 100)],
+		vec![
+			(1, None, 20, Some(Perbill::from_percent(10))),
+			(2, None, 20, None),
+			(3, Some(1), 10, None),
+			(3, Some(2), 15, None),
+		],
+	)
+	.execute_with(|| {
+		assert_eq!(Stake::candidate_state(1).unwrap().fee, Perbill::from_percent(10));
+		assert_eq!(Stake::candidate_state(2).unwrap().fee, Perbill::zero());
+		let nominator = Stake::nominator_state(3).unwrap();
+		assert_eq!(nominator.nominations.0.len(), 2);
+		assert_eq!(nominator.total, 25);
+		assert_eq!(Balances::reserved_balance(&3), 25);
+		assert_eq!(Stake::candidate_state(2).unwrap().total, 35);
+	});
+}
+
+#[test]
+#[should_panic(expected = "Genesis nominator 2 failed to nominate 9")]
+fn genesis_fails_on_nomination_of_unknown_candidate() {
+	genesis(
+		vec![(1, 100), (2, 100)],
+		vec![(1, None, 20, None), (2, Some(9), 10, None)],
+	);
+}
+
+#[test]
+#[should_panic(expected = "Genesis candidate 1 failed to join candidates")]
+fn genesis_fails_on_candidate_bond_below_min() {
+	genesis(vec![(1, 100)], vec![(1, None, 5, None)]);
+}
+
 #[test]
 fn online_offline_works() {
 	two_validators_four_nominators().execute_with(|| {
@@ -88,21 +136,21 @@ fn online_offline_works() {
 		assert_ok!(Stake::go_offline(Origin::signed(2)));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::ValidatorWentOffline(3, 2))
+			MetaEvent::stake(Event::ValidatorWentOffline(3, 2))
 		);
 		roll_to(21);
 		let mut expected = vec![
-			RawEvent::ValidatorChosen(2, 1, 700),
-			RawEvent::ValidatorChosen(2, 2, 400),
-			RawEvent::NewRound(5, 2, 2, 1100),
-			RawEvent::ValidatorChosen(3, 1, 700),
-			RawEvent::ValidatorChosen(3, 2, 400),
-			RawEvent::NewRound(10, 3, 2, 1100),
-			RawEvent::ValidatorWentOffline(3, 2),
-			RawEvent::ValidatorChosen(4, 1, 700),
-			RawEvent::NewRound(15, 4, 1, 700),
-			RawEvent::ValidatorChosen(5, 1, 700),
-			RawEvent::NewRound(20, 5, 1, 700),
+			Event::ValidatorChosen(2, 1, 700),
+			Event::ValidatorChosen(2, 2, 400),
+			Event::NewRound(5, 2, 2, 1100),
+			Event::ValidatorChosen(3, 1, 700),
+			Event::ValidatorChosen(3, 2, 400),
+			Event::NewRound(10, 3, 2, 1100),
+			Event::ValidatorWentOffline(3, 2),
+			Event::ValidatorChosen(4, 1, 700),
+			Event::NewRound(15, 4, 1, 700),
+			Event::ValidatorChosen(5, 1, 700),
+			Event::NewRound(20, 5, 1, 700),
 		];
 		assert_eq!(events(), expected);
 		assert_noop!(
@@ -112,13 +160,13 @@ fn online_offline_works() {
 		assert_ok!(Stake::go_online(Origin::signed(2)));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::ValidatorBackOnline(5, 2))
+			MetaEvent::stake(Event::ValidatorBackOnline(5, 2))
 		);
-		expected.push(RawEvent::ValidatorBackOnline(5, 2));
+		expected.push(Event::ValidatorBackOnline(5, 2));
 		roll_to(26);
-		expected.push(RawEvent::ValidatorChosen(6, 1, 700));
-		expected.push(RawEvent::ValidatorChosen(6, 2, 400));
-		expected.push(RawEvent::NewRound(25, 6, 2, 1100));
+		expected.push(Event::ValidatorChosen(6, 1, 700));
+		expected.push(Event::ValidatorChosen(6, 2, 400));
+		expected.push(Event::NewRound(25, 6, 2, 1100));
 		assert_eq!(events(), expected);
 	});
 }
@@ -158,7 +206,7 @@ fn join_validator_candidates() {
 		));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::JoinedValidatorCandidates(7, 10u128, 1110u128))
+			MetaEvent::stake(Event::JoinedValidatorCandidates(7, 10u128, 1110u128))
 		);
 	});
 }
@@ -175,7 +223,7 @@ fn validator_exit_executes_after_delay() {
 		assert_ok!(Stake::leave_candidates(Origin::signed(2)));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::ValidatorScheduledExit(3, 2, 5))
+			MetaEvent::stake(Event::ValidatorScheduledExit(3, 2, 5))
 		);
 		let info = <Stake as Store>::Candidates::get(&2).unwrap();
 		assert_eq!(info.state, ValidatorStatus::Leaving(5));
@@ -184,18 +232,20 @@ fn validator_exit_executes_after_delay() {
 		// holding them retroactively accountable for previous faults
 		// (within the last T::SlashingWindow blocks)
 		let expected = vec![
-			RawEvent::ValidatorChosen(2, 1, 700),
-			RawEvent::ValidatorChosen(2, 2, 400),
-			RawEvent::NewRound(5, 2, 2, 1100),
-			RawEvent::ValidatorChosen(3, 1, 700),
-			RawEvent::ValidatorChosen(3, 2, 400),
-			RawEvent::NewRound(10, 3, 2, 1100),
-			RawEvent::ValidatorScheduledExit(3, 2, 5),
-			RawEvent::ValidatorChosen(4, 1, 700),
-			RawEvent::NewRound(15, 4, 1, 700),
-			RawEvent::ValidatorLeft(2, 400, 700),
-			RawEvent::ValidatorChosen(5, 1, 700),
-			RawEvent::NewRound(20, 5, 1, 700),
+			Event::ValidatorChosen(2, 1, 700),
+			Event::ValidatorChosen(2, 2, 400),
+			Event::NewRound(5, 2, 2, 1100),
+			Event::ValidatorChosen(3, 1, 700),
+			Event::ValidatorChosen(3, 2, 400),
+			Event::NewRound(10, 3, 2, 1100),
+			Event::ValidatorScheduledExit(3, 2, 5),
+			Event::ValidatorChosen(4, 1, 700),
+			Event::NewRound(15, 4, 1, 700),
+			Event::NominationUnbonding(5, 2, 100, 6),
+			Event::NominationUnbonding(6, 2, 100, 6),
+			Event::ValidatorLeft(2, 400, 700),
+			Event::ValidatorChosen(5, 1, 700),
+			Event::NewRound(20, 5, 1, 700),
 		];
 		assert_eq!(events(), expected);
 	});
@@ -207,18 +257,18 @@ fn validator_selection_chooses_top_candidates() {
 		roll_to(8);
 		// should choose top MaxValidators (5), in order
 		let expected = vec![
-			RawEvent::ValidatorChosen(2, 1, 100),
-			RawEvent::ValidatorChosen(2, 2, 90),
-			RawEvent::ValidatorChosen(2, 3, 80),
-			RawEvent::ValidatorChosen(2, 4, 70),
-			RawEvent::ValidatorChosen(2, 5, 60),
-			RawEvent::NewRound(5, 2, 5, 400),
+			Event::ValidatorChosen(2, 1, 100),
+			Event::ValidatorChosen(2, 2, 90),
+			Event::ValidatorChosen(2, 3, 80),
+			Event::ValidatorChosen(2, 4, 70),
+			Event::ValidatorChosen(2, 5, 60),
+			Event::NewRound(5, 2, 5, 400),
 		];
 		assert_eq!(events(), expected);
 		assert_ok!(Stake::leave_candidates(Origin::signed(6)));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::ValidatorScheduledExit(2, 6, 4))
+			MetaEvent::stake(Event::ValidatorScheduledExit(2, 6, 4))
 		);
 		roll_to(21);
 		assert_ok!(Stake::join_candidates(
@@ -228,79 +278,110 @@ fn validator_selection_chooses_top_candidates() {
 		));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::JoinedValidatorCandidates(6, 69u128, 469u128))
+			MetaEvent::stake(Event::JoinedValidatorCandidates(6, 69u128, 469u128))
 		);
 		roll_to(27);
 		// should choose top MaxValidators (5), in order
 		let expected = vec![
-			RawEvent::ValidatorChosen(2, 1, 100),
-			RawEvent::ValidatorChosen(2, 2, 90),
-			RawEvent::ValidatorChosen(2, 3, 80),
-			RawEvent::ValidatorChosen(2, 4, 70),
-			RawEvent::ValidatorChosen(2, 5, 60),
-			RawEvent::NewRound(5, 2, 5, 400),
-			RawEvent::ValidatorScheduledExit(2, 6, 4),
-			RawEvent::ValidatorChosen(3, 1, 100),
-			RawEvent::ValidatorChosen(3, 2, 90),
-			RawEvent::ValidatorChosen(3, 3, 80),
-			RawEvent::ValidatorChosen(3, 4, 70),
-			RawEvent::ValidatorChosen(3, 5, 60),
-			RawEvent::NewRound(10, 3, 5, 400),
-			RawEvent::ValidatorLeft(6, 50, 400),
-			RawEvent::ValidatorChosen(4, 1, 100),
-			RawEvent::ValidatorChosen(4, 2, 90),
-			RawEvent::ValidatorChosen(4, 3, 80),
-			RawEvent::ValidatorChosen(4, 4, 70),
-			RawEvent::ValidatorChosen(4, 5, 60),
-			RawEvent::NewRound(15, 4, 5, 400),
-			RawEvent::ValidatorChosen(5, 1, 100),
-			RawEvent::ValidatorChosen(5, 2, 90),
-			RawEvent::ValidatorChosen(5, 3, 80),
-			RawEvent::ValidatorChosen(5, 4, 70),
-			RawEvent::ValidatorChosen(5, 5, 60),
-			RawEvent::NewRound(20, 5, 5, 400),
-			RawEvent::JoinedValidatorCandidates(6, 69, 469),
-			RawEvent::ValidatorChosen(6, 1, 100),
-			RawEvent::ValidatorChosen(6, 2, 90),
-			RawEvent::ValidatorChosen(6, 3, 80),
-			RawEvent::ValidatorChosen(6, 4, 70),
-			RawEvent::ValidatorChosen(6, 6, 69),
-			RawEvent::NewRound(25, 6, 5, 409),
+			Event::ValidatorChosen(2, 1, 100),
+			Event::ValidatorChosen(2, 2, 90),
+			Event::ValidatorChosen(2, 3, 80),
+			Event::ValidatorChosen(2, 4, 70),
+			Event::ValidatorChosen(2, 5, 60),
+			Event::NewRound(5, 2, 5, 400),
+			Event::ValidatorScheduledExit(2, 6, 4),
+			Event::ValidatorChosen(3, 1, 100),
+			Event::ValidatorChosen(3, 2, 90),
+			Event::ValidatorChosen(3, 3, 80),
+			Event::ValidatorChosen(3, 4, 70),
+			Event::ValidatorChosen(3, 5, 60),
+			Event::NewRound(10, 3, 5, 400),
+			Event::ValidatorLeft(6, 50, 400),
+			Event::ValidatorChosen(4, 1, 100),
+			Event::ValidatorChosen(4, 2, 90),
+			Event::ValidatorChosen(4, 3, 80),
+			Event::ValidatorChosen(4, 4, 70),
+			Event::ValidatorChosen(4, 5, 60),
+			Event::NewRound(15, 4, 5, 400),
+			Event::ValidatorChosen(5, 1, 100),
+			Event::ValidatorChosen(5, 2, 90),
+			Event::ValidatorChosen(5, 3, 80),
+			Event::ValidatorChosen(5, 4, 70),
+			Event::ValidatorChosen(5, 5, 60),
+			Event::NewRound(20, 5, 5, 400),
+			Event::JoinedValidatorCandidates(6, 69, 469),
+			Event::ValidatorChosen(6, 1, 100),
+			Event::ValidatorChosen(6, 2, 90),
+			Event::ValidatorChosen(6, 3, 80),
+			Event::ValidatorChosen(6, 4, 70),
+			Event::ValidatorChosen(6, 6, 69),
+			Event::NewRound(25, 6, 5, 409),
 		];
 		assert_eq!(events(), expected);
 	});
 }
 
+#[test]
+fn candidate_pool_is_ordered_by_stake() {
+	five_validators_no_nominators().execute_with(|| {
+		let pool = || {
+			<CandidatesByStake<Test>>::iter()
+				.map(|(_, acc, _)| acc)
+				.collect::<Vec<u64>>()
+		};
+		assert_eq!(pool(), vec![1, 2, 3, 4, 5, 6]);
+		assert_eq!(Stake::candidate_pool(6), Some(50));
+		// bonding more moves the candidate up
+		assert_ok!(Stake::candidate_bond_more(Origin::signed(6), 45));
+		assert_eq!(pool(), vec![1, 6, 2, 3, 4, 5]);
+		assert_eq!(Stake::candidate_pool(6), Some(95));
+		// bonding less moves the candidate down
+		assert_ok!(Stake::candidate_bond_less(Origin::signed(1), 50));
+		assert_eq!(pool(), vec![6, 2, 3, 4, 5, 1]);
+		// offline candidates are removed from the pool
+		assert_ok!(Stake::go_offline(Origin::signed(2)));
+		assert_eq!(pool(), vec![6, 3, 4, 5, 1]);
+		assert_eq!(Stake::candidate_pool(2), None);
+		assert_ok!(Stake::go_online(Origin::signed(2)));
+		assert_eq!(pool(), vec![6, 2, 3, 4, 5, 1]);
+		// as are candidates that are leaving
+		assert_ok!(Stake::leave_candidates(Origin::signed(3)));
+		assert_eq!(pool(), vec![6, 2, 4, 5, 1]);
+		roll_to(5);
+		assert_eq!(Stake::validators(), vec![1, 2, 4, 5, 6]);
+	});
+}
+
 #[test]
 fn exit_queue() {
 	five_validators_no_nominators().execute_with(|| {
 		roll_to(8);
 		// should choose top MaxValidators (5), in order
 		let mut expected = vec![
-			RawEvent::ValidatorChosen(2, 1, 100),
-			RawEvent::ValidatorChosen(2, 2, 90),
-			RawEvent::ValidatorChosen(2, 3, 80),
-			RawEvent::ValidatorChosen(2, 4, 70),
-			RawEvent::ValidatorChosen(2, 5, 60),
-			RawEvent::NewRound(5, 2, 5, 400),
+			Event::ValidatorChosen(2, 1, 100),
+			Event::ValidatorChosen(2, 2, 90),
+			Event::ValidatorChosen(2, 3, 80),
+			Event::ValidatorChosen(2, 4, 70),
+			Event::ValidatorChosen(2, 5, 60),
+			Event::NewRound(5, 2, 5, 400),
 		];
 		assert_eq!(events(), expected);
 		assert_ok!(Stake::leave_candidates(Origin::signed(6)));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::ValidatorScheduledExit(2, 6, 4))
+			MetaEvent::stake(Event::ValidatorScheduledExit(2, 6, 4))
 		);
 		roll_to(11);
 		assert_ok!(Stake::leave_candidates(Origin::signed(5)));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::ValidatorScheduledExit(3, 5, 5))
+			MetaEvent::stake(Event::ValidatorScheduledExit(3, 5, 5))
 		);
 		roll_to(16);
 		assert_ok!(Stake::leave_candidates(Origin::signed(4)));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::ValidatorScheduledExit(4, 4, 6))
+			MetaEvent::stake(Event::ValidatorScheduledExit(4, 4, 6))
 		);
 		assert_noop!(
 			Stake::leave_candidates(Origin::signed(4)),
@@ -308,26 +389,26 @@ fn exit_queue() {
 		);
 		roll_to(21);
 		let mut new_events = vec![
-			RawEvent::ValidatorScheduledExit(2, 6, 4),
-			RawEvent::ValidatorChosen(3, 1, 100),
-			RawEvent::ValidatorChosen(3, 2, 90),
-			RawEvent::ValidatorChosen(3, 3, 80),
-			RawEvent::ValidatorChosen(3, 4, 70),
-			RawEvent::ValidatorChosen(3, 5, 60),
-			RawEvent::NewRound(10, 3, 5, 400),
-			RawEvent::ValidatorScheduledExit(3, 5, 5),
-			RawEvent::ValidatorLeft(6, 50, 400),
-			RawEvent::ValidatorChosen(4, 1, 100),
-			RawEvent::ValidatorChosen(4, 2, 90),
-			RawEvent::ValidatorChosen(4, 3, 80),
-			RawEvent::ValidatorChosen(4, 4, 70),
-			RawEvent::NewRound(15, 4, 4, 340),
-			RawEvent::ValidatorScheduledExit(4, 4, 6),
-			RawEvent::ValidatorLeft(5, 60, 340),
-			RawEvent::ValidatorChosen(5, 1, 100),
-			RawEvent::ValidatorChosen(5, 2, 90),
-			RawEvent::ValidatorChosen(5, 3, 80),
-			RawEvent::NewRound(20, 5, 3, 270),
+			Event::ValidatorScheduledExit(2, 6, 4),
+			Event::ValidatorChosen(3, 1, 100),
+			Event::ValidatorChosen(3, 2, 90),
+			Event::ValidatorChosen(3, 3, 80),
+			Event::ValidatorChosen(3, 4, 70),
+			Event::ValidatorChosen(3, 5, 60),
+			Event::NewRound(10, 3, 5, 400),
+			Event::ValidatorScheduledExit(3, 5, 5),
+			Event::ValidatorLeft(6, 50, 400),
+			Event::ValidatorChosen(4, 1, 100),
+			Event::ValidatorChosen(4, 2, 90),
+			Event::ValidatorChosen(4, 3, 80),
+			Event::ValidatorChosen(4, 4, 70),
+			Event::NewRound(15, 4, 4, 340),
+			Event::ValidatorScheduledExit(4, 4, 6),
+			Event::ValidatorLeft(5, 60, 340),
+			Event::ValidatorChosen(5, 1, 100),
+			Event::ValidatorChosen(5, 2, 90),
+			Event::ValidatorChosen(5, 3, 80),
+			Event::NewRound(20, 5, 3, 270),
 		];
 		expected.append(&mut new_events);
 		assert_eq!(events(), expected);
@@ -340,32 +421,33 @@ fn payout_distribution_to_solo_validators() {
 		roll_to(8);
 		// should choose top MaxValidators (5), in order
 		let mut expected = vec![
-			RawEvent::ValidatorChosen(2, 1, 100),
-			RawEvent::ValidatorChosen(2, 2, 90),
-			RawEvent::ValidatorChosen(2, 3, 80),
-			RawEvent::ValidatorChosen(2, 4, 70),
-			RawEvent::ValidatorChosen(2, 5, 60),
-			RawEvent::NewRound(5, 2, 5, 400),
+			Event::ValidatorChosen(2, 1, 100),
+			Event::ValidatorChosen(2, 2, 90),
+			Event::ValidatorChosen(2, 3, 80),
+			Event::ValidatorChosen(2, 4, 70),
+			Event::ValidatorChosen(2, 5, 60),
+			Event::NewRound(5, 2, 5, 400),
 		];
 		assert_eq!(events(), expected);
 		// ~ set block author as 1 for all blocks this round
 		set_author(2, 1, 100);
 		roll_to(16);
 		// pay total issuance to 1
+		assert_ok!(Stake::claim_rewards(Origin::signed(1), 2, 1));
 		let mut new = vec![
-			RawEvent::ValidatorChosen(3, 1, 100),
-			RawEvent::ValidatorChosen(3, 2, 90),
-			RawEvent::ValidatorChosen(3, 3, 80),
-			RawEvent::ValidatorChosen(3, 4, 70),
-			RawEvent::ValidatorChosen(3, 5, 60),
-			RawEvent::NewRound(10, 3, 5, 400),
-			RawEvent::Rewarded(1, 305),
-			RawEvent::ValidatorChosen(4, 1, 100),
-			RawEvent::ValidatorChosen(4, 2, 90),
-			RawEvent::ValidatorChosen(4, 3, 80),
-			RawEvent::ValidatorChosen(4, 4, 70),
-			RawEvent::ValidatorChosen(4, 5, 60),
-			RawEvent::NewRound(15, 4, 5, 400),
+			Event::ValidatorChosen(3, 1, 100),
+			Event::ValidatorChosen(3, 2, 90),
+			Event::ValidatorChosen(3, 3, 80),
+			Event::ValidatorChosen(3, 4, 70),
+			Event::ValidatorChosen(3, 5, 60),
+			Event::NewRound(10, 3, 5, 400),
+			Event::ValidatorChosen(4, 1, 100),
+			Event::ValidatorChosen(4, 2, 90),
+			Event::ValidatorChosen(4, 3, 80),
+			Event::ValidatorChosen(4, 4, 70),
+			Event::ValidatorChosen(4, 5, 60),
+			Event::NewRound(15, 4, 5, 400),
+			Event::Rewarded(1, 305),
 		];
 		expected.append(&mut new);
 		assert_eq!(events(), expected);
@@ -375,21 +457,23 @@ fn payout_distribution_to_solo_validators() {
 		set_author(4, 2, 40);
 		roll_to(26);
 		// pay 60% total issuance to 1 and 40% total issuance to 2
+		assert_ok!(Stake::claim_rewards(Origin::signed(1), 4, 1));
+		assert_ok!(Stake::claim_rewards(Origin::signed(1), 4, 2));
 		let mut new1 = vec![
-			RawEvent::ValidatorChosen(5, 1, 100),
-			RawEvent::ValidatorChosen(5, 2, 90),
-			RawEvent::ValidatorChosen(5, 3, 80),
-			RawEvent::ValidatorChosen(5, 4, 70),
-			RawEvent::ValidatorChosen(5, 5, 60),
-			RawEvent::NewRound(20, 5, 5, 400),
-			RawEvent::Rewarded(1, 192),
-			RawEvent::Rewarded(2, 128),
-			RawEvent::ValidatorChosen(6, 1, 100),
-			RawEvent::ValidatorChosen(6, 2, 90),
-			RawEvent::ValidatorChosen(6, 3, 80),
-			RawEvent::ValidatorChosen(6, 4, 70),
-			RawEvent::ValidatorChosen(6, 5, 60),
-			RawEvent::NewRound(25, 6, 5, 400),
+			Event::ValidatorChosen(5, 1, 100),
+			Event::ValidatorChosen(5, 2, 90),
+			Event::ValidatorChosen(5, 3, 80),
+			Event::ValidatorChosen(5, 4, 70),
+			Event::ValidatorChosen(5, 5, 60),
+			Event::NewRound(20, 5, 5, 400),
+			Event::ValidatorChosen(6, 1, 100),
+			Event::ValidatorChosen(6, 2, 90),
+			Event::ValidatorChosen(6, 3, 80),
+			Event::ValidatorChosen(6, 4, 70),
+			Event::ValidatorChosen(6, 5, 60),
+			Event::NewRound(25, 6, 5, 400),
+			Event::Rewarded(1, 192),
+			Event::Rewarded(2, 128),
 		];
 		expected.append(&mut new1);
 		assert_eq!(events(), expected);
@@ -401,28 +485,31 @@ fn payout_distribution_to_solo_validators() {
 		set_author(6, 5, 20);
 		roll_to(36);
 		// pay 20% issuance for all validators
+		for validator in 1..=5 {
+			assert_ok!(Stake::claim_rewards(Origin::signed(6), 6, validator));
+		}
 		let mut new2 = vec![
-			RawEvent::ValidatorChosen(7, 1, 100),
-			RawEvent::ValidatorChosen(7, 2, 90),
-			RawEvent::ValidatorChosen(7, 3, 80),
-			RawEvent::ValidatorChosen(7, 4, 70),
-			RawEvent::ValidatorChosen(7, 5, 60),
-			RawEvent::NewRound(30, 7, 5, 400),
-			RawEvent::Rewarded(5, 67),
-			RawEvent::Rewarded(3, 67),
-			RawEvent::Rewarded(1, 67),
-			RawEvent::Rewarded(4, 67),
-			RawEvent::Rewarded(2, 67),
-			RawEvent::ValidatorChosen(8, 1, 100),
-			RawEvent::ValidatorChosen(8, 2, 90),
-			RawEvent::ValidatorChosen(8, 3, 80),
-			RawEvent::ValidatorChosen(8, 4, 70),
-			RawEvent::ValidatorChosen(8, 5, 60),
-			RawEvent::NewRound(35, 8, 5, 400),
+			Event::ValidatorChosen(7, 1, 100),
+			Event::ValidatorChosen(7, 2, 90),
+			Event::ValidatorChosen(7, 3, 80),
+			Event::ValidatorChosen(7, 4, 70),
+			Event::ValidatorChosen(7, 5, 60),
+			Event::NewRound(30, 7, 5, 400),
+			Event::ValidatorChosen(8, 1, 100),
+			Event::ValidatorChosen(8, 2, 90),
+			Event::ValidatorChosen(8, 3, 80),
+			Event::ValidatorChosen(8, 4, 70),
+			Event::ValidatorChosen(8, 5, 60),
+			Event::NewRound(35, 8, 5, 400),
+			Event::Rewarded(1, 67),
+			Event::Rewarded(2, 67),
+			Event::Rewarded(3, 67),
+			Event::Rewarded(4, 67),
+			Event::Rewarded(5, 67),
 		];
 		expected.append(&mut new2);
 		assert_eq!(events(), expected);
-		// check that distributing rewards clears awarded pts
+		// check that claiming rewards clears awarded pts
 		assert!(<Stake as Store>::AwardedPts::get(1, 1).is_zero());
 		assert!(<Stake as Store>::AwardedPts::get(4, 1).is_zero());
 		assert!(<Stake as Store>::AwardedPts::get(4, 2).is_zero());
@@ -440,8 +527,8 @@ fn validator_commission() {
 		roll_to(8);
 		// chooses top MaxValidators (5), in order
 		let mut expected = vec![
-			RawEvent::ValidatorChosen(2, 1, 40),
-			RawEvent::NewRound(5, 2, 1, 40),
+			Event::ValidatorChosen(2, 1, 40),
+			Event::NewRound(5, 2, 1, 40),
 		];
 		assert_eq!(events(), expected);
 		assert_ok!(Stake::join_candidates(
@@ -451,57 +538,113 @@ fn validator_commission() {
 		));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::JoinedValidatorCandidates(4, 20u128, 60u128))
+			MetaEvent::stake(Event::JoinedValidatorCandidates(4, 20u128, 60u128))
 		);
 		roll_to(9);
 		assert_ok!(Stake::join_nominators(Origin::signed(5), 4, 10));
 		assert_ok!(Stake::join_nominators(Origin::signed(6), 4, 10));
 		roll_to(11);
 		let mut new = vec![
-			RawEvent::JoinedValidatorCandidates(4, 20, 60),
-			RawEvent::ValidatorNominated(5, 10, 4, 30),
-			RawEvent::NominatorJoined(5, 10),
-			RawEvent::ValidatorNominated(6, 10, 4, 40),
-			RawEvent::NominatorJoined(6, 10),
-			RawEvent::ValidatorChosen(3, 4, 40),
-			RawEvent::ValidatorChosen(3, 1, 40),
-			RawEvent::NewRound(10, 3, 2, 80),
+			Event::JoinedValidatorCandidates(4, 20, 60),
+			Event::ValidatorNominated(5, 10, 4, 30),
+			Event::NominatorJoined(5, 10),
+			Event::ValidatorNominated(6, 10, 4, 40),
+			Event::NominatorJoined(6, 10),
+			Event::ValidatorChosen(3, 1, 40),
+			Event::ValidatorChosen(3, 4, 40),
+			Event::NewRound(10, 3, 2, 80),
 		];
 		expected.append(&mut new);
 		assert_eq!(events(), expected);
 		// only reward author with id 4
 		set_author(3, 4, 100);
 		roll_to(21);
+		assert_ok!(Stake::claim_rewards(Origin::signed(5), 3, 4));
 		// 20% of 10 is commission + due_portion (4) = 2 + 4 = 6
 		// all nominator payouts are 10-2 = 8 * stake_pct
 		let mut new2 = vec![
-			RawEvent::ValidatorChosen(4, 4, 40),
-			RawEvent::ValidatorChosen(4, 1, 40),
-			RawEvent::NewRound(15, 4, 2, 80),
-			RawEvent::Rewarded(4, 18),
-			RawEvent::Rewarded(5, 6),
-			RawEvent::Rewarded(6, 6),
-			RawEvent::ValidatorChosen(5, 4, 40),
-			RawEvent::ValidatorChosen(5, 1, 40),
-			RawEvent::NewRound(20, 5, 2, 80),
+			Event::ValidatorChosen(4, 1, 40),
+			Event::ValidatorChosen(4, 4, 40),
+			Event::NewRound(15, 4, 2, 80),
+			Event::ValidatorChosen(5, 1, 40),
+			Event::ValidatorChosen(5, 4, 40),
+			Event::NewRound(20, 5, 2, 80),
+			Event::Rewarded(4, 18),
+			Event::Rewarded(5, 6),
+			Event::Rewarded(6, 6),
 		];
 		expected.append(&mut new2);
 		assert_eq!(events(), expected);
 	});
 }
 
+#[test]
+fn commission_change_applies_after_delay() {
+	one_validator_two_nominators().execute_with(|| {
+		assert_noop!(
+			Stake::set_commission(Origin::signed(2), Perbill::from_percent(20)),
+			Error::<Test>::CandidateDNE
+		);
+		assert_noop!(
+			Stake::set_commission(Origin::signed(1), Perbill::from_percent(60)),
+			Error::<Test>::FeeOverMax
+		);
+		assert_ok!(Stake::set_commission(
+			Origin::signed(1),
+			Perbill::from_percent(20)
+		));
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::CommissionChangeScheduled(
+				1,
+				Perbill::zero(),
+				Perbill::from_percent(20),
+				3
+			))
+		);
+		// a new request replaces the pending one
+		assert_ok!(Stake::set_commission(
+			Origin::signed(1),
+			Perbill::from_percent(10)
+		));
+		assert_eq!(
+			Stake::pending_commission(1),
+			Some((Perbill::from_percent(10), 3))
+		);
+		roll_to(6);
+		assert_eq!(Stake::candidate_state(1).unwrap().fee, Perbill::zero());
+		roll_to(11);
+		assert!(events().contains(&Event::CommissionChanged(
+			1,
+			Perbill::zero(),
+			Perbill::from_percent(10)
+		)));
+		assert_eq!(
+			Stake::candidate_state(1).unwrap().fee,
+			Perbill::from_percent(10)
+		);
+		assert_eq!(Stake::pending_commission(1), None);
+		// snapshots taken before the change keep the old commission
+		assert_eq!(<Stake as Store>::AtStake::get(2, 1).fee, Perbill::zero());
+		assert_eq!(
+			<Stake as Store>::AtStake::get(3, 1).fee,
+			Perbill::from_percent(10)
+		);
+	});
+}
+
 #[test]
 fn multiple_nominations() {
 	five_validators_five_nominators().execute_with(|| {
 		roll_to(8);
 		// chooses top MaxValidators (5), in order
 		let mut expected = vec![
-			RawEvent::ValidatorChosen(2, 1, 50),
-			RawEvent::ValidatorChosen(2, 2, 40),
-			RawEvent::ValidatorChosen(2, 4, 20),
-			RawEvent::ValidatorChosen(2, 3, 20),
-			RawEvent::ValidatorChosen(2, 5, 10),
-			RawEvent::NewRound(5, 2, 5, 140),
+			Event::ValidatorChosen(2, 1, 50),
+			Event::ValidatorChosen(2, 2, 40),
+			Event::ValidatorChosen(2, 3, 20),
+			Event::ValidatorChosen(2, 4, 20),
+			Event::ValidatorChosen(2, 5, 10),
+			Event::NewRound(5, 2, 5, 140),
 		];
 		assert_eq!(events(), expected);
 		assert_noop!(
@@ -529,21 +672,21 @@ fn multiple_nominations() {
 		);
 		roll_to(16);
 		let mut new = vec![
-			RawEvent::ValidatorNominated(6, 10, 2, 50),
-			RawEvent::ValidatorNominated(6, 10, 3, 30),
-			RawEvent::ValidatorNominated(6, 10, 4, 30),
-			RawEvent::ValidatorChosen(3, 2, 50),
-			RawEvent::ValidatorChosen(3, 1, 50),
-			RawEvent::ValidatorChosen(3, 4, 30),
-			RawEvent::ValidatorChosen(3, 3, 30),
-			RawEvent::ValidatorChosen(3, 5, 10),
-			RawEvent::NewRound(10, 3, 5, 170),
-			RawEvent::ValidatorChosen(4, 2, 50),
-			RawEvent::ValidatorChosen(4, 1, 50),
-			RawEvent::ValidatorChosen(4, 4, 30),
-			RawEvent::ValidatorChosen(4, 3, 30),
-			RawEvent::ValidatorChosen(4, 5, 10),
-			RawEvent::NewRound(15, 4, 5, 170),
+			Event::ValidatorNominated(6, 10, 2, 50),
+			Event::ValidatorNominated(6, 10, 3, 30),
+			Event::ValidatorNominated(6, 10, 4, 30),
+			Event::ValidatorChosen(3, 1, 50),
+			Event::ValidatorChosen(3, 2, 50),
+			Event::ValidatorChosen(3, 3, 30),
+			Event::ValidatorChosen(3, 4, 30),
+			Event::ValidatorChosen(3, 5, 10),
+			Event::NewRound(10, 3, 5, 170),
+			Event::ValidatorChosen(4, 1, 50),
+			Event::ValidatorChosen(4, 2, 50),
+			Event::ValidatorChosen(4, 3, 30),
+			Event::ValidatorChosen(4, 4, 30),
+			Event::ValidatorChosen(4, 5, 10),
+			Event::NewRound(15, 4, 5, 170),
 		];
 		expected.append(&mut new);
 		assert_eq!(events(), expected);
@@ -563,35 +706,35 @@ fn multiple_nominations() {
 		);
 		roll_to(26);
 		let mut new2 = vec![
-			RawEvent::ValidatorChosen(5, 2, 50),
-			RawEvent::ValidatorChosen(5, 1, 50),
-			RawEvent::ValidatorChosen(5, 4, 30),
-			RawEvent::ValidatorChosen(5, 3, 30),
-			RawEvent::ValidatorChosen(5, 5, 10),
-			RawEvent::NewRound(20, 5, 5, 170),
-			RawEvent::ValidatorNominated(7, 80, 2, 130),
-			RawEvent::ValidatorChosen(6, 2, 130),
-			RawEvent::ValidatorChosen(6, 1, 50),
-			RawEvent::ValidatorChosen(6, 4, 30),
-			RawEvent::ValidatorChosen(6, 3, 30),
-			RawEvent::ValidatorChosen(6, 5, 10),
-			RawEvent::NewRound(25, 6, 5, 250),
+			Event::ValidatorChosen(5, 1, 50),
+			Event::ValidatorChosen(5, 2, 50),
+			Event::ValidatorChosen(5, 3, 30),
+			Event::ValidatorChosen(5, 4, 30),
+			Event::ValidatorChosen(5, 5, 10),
+			Event::NewRound(20, 5, 5, 170),
+			Event::ValidatorNominated(7, 80, 2, 130),
+			Event::ValidatorChosen(6, 2, 130),
+			Event::ValidatorChosen(6, 1, 50),
+			Event::ValidatorChosen(6, 3, 30),
+			Event::ValidatorChosen(6, 4, 30),
+			Event::ValidatorChosen(6, 5, 10),
+			Event::NewRound(25, 6, 5, 250),
 		];
 		expected.append(&mut new2);
 		assert_eq!(events(), expected);
 		assert_ok!(Stake::leave_candidates(Origin::signed(2)));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::ValidatorScheduledExit(6, 2, 8))
+			MetaEvent::stake(Event::ValidatorScheduledExit(6, 2, 8))
 		);
 		roll_to(31);
 		let mut new3 = vec![
-			RawEvent::ValidatorScheduledExit(6, 2, 8),
-			RawEvent::ValidatorChosen(7, 1, 50),
-			RawEvent::ValidatorChosen(7, 4, 30),
-			RawEvent::ValidatorChosen(7, 3, 30),
-			RawEvent::ValidatorChosen(7, 5, 10),
-			RawEvent::NewRound(30, 7, 4, 120),
+			Event::ValidatorScheduledExit(6, 2, 8),
+			Event::ValidatorChosen(7, 1, 50),
+			Event::ValidatorChosen(7, 3, 30),
+			Event::ValidatorChosen(7, 4, 30),
+			Event::ValidatorChosen(7, 5, 10),
+			Event::NewRound(30, 7, 4, 120),
 		];
 		expected.append(&mut new3);
 		assert_eq!(events(), expected);
@@ -619,6 +762,11 @@ fn multiple_nominations() {
 		assert_eq!(Balances::free_balance(&6), 60);
 		assert_eq!(Balances::free_balance(&7), 10);
 		roll_to(40);
+		// nominations to the exited validator are unbonding
+		assert_eq!(Balances::reserved_balance(&6), 40);
+		assert_eq!(Balances::reserved_balance(&7), 90);
+		assert_eq!(<Stake as Store>::Unbonding::get(7)[0].amount, 80);
+		roll_to(46);
 		assert_eq!(<Stake as Store>::Nominators::get(7).unwrap().total, 10);
 		assert_eq!(<Stake as Store>::Nominators::get(6).unwrap().total, 30);
 		assert_eq!(
@@ -644,6 +792,238 @@ fn multiple_nominations() {
 	});
 }
 
+#[test]
+fn larger_nomination_evicts_lowest_nomination_of_full_validator() {
+	five_validators_five_nominators().execute_with(|| {
+		assert_eq!(Stake::min_nomination_to_enter(&1), Some(3));
+		assert_eq!(Stake::min_nomination_to_enter(&11), None);
+		assert_ok!(Stake::nominate_new(Origin::signed(8), 1, 15));
+		// 1 is full, a new nomination must be larger than the lowest one
+		assert_eq!(Stake::min_nomination_to_enter(&1), Some(11));
+		assert_noop!(
+			Stake::nominate_new(Origin::signed(9), 1, 10),
+			Error::<Test>::TooManyNominators
+		);
+		assert_ok!(Stake::nominate_new(Origin::signed(9), 1, 12));
+		// 6, 7 and 10 nominate 10, the nomination of the smallest account is evicted
+		let expected = vec![
+			Event::NominationEvicted(6, 1, 10),
+			Event::NominatorLeft(6, 10),
+			Event::NominationUnbonding(6, 1, 10, 3),
+			Event::ValidatorNominated(9, 12, 1, 67),
+		];
+		let mut recent = events();
+		assert_eq!(recent.split_off(recent.len() - 4), expected);
+		assert!(!Stake::is_nominator(&6));
+		assert!(!Stake::candidate_state(1)
+			.unwrap()
+			.nominators
+			.0
+			.iter()
+			.any(|x| x.owner == 6));
+		assert_eq!(Stake::nominator_state(9).unwrap().total, 22);
+		assert_eq!(Stake::min_nomination_to_enter(&1), Some(11));
+		// the evicted stake stays reserved until it is unbonded
+		assert_eq!(Balances::reserved_balance(&6), 10);
+		roll_to(11);
+		assert_eq!(Balances::reserved_balance(&6), 0);
+		assert!(events().contains(&Event::Unbonded(6, 10)));
+	});
+}
+
+#[test]
+fn switch_nomination_evicts_lowest_nomination_of_full_validator() {
+	five_validators_five_nominators().execute_with(|| {
+		assert_ok!(Stake::nominate_new(Origin::signed(8), 1, 15));
+		// 1 is full, the switched nomination must be larger than the lowest one
+		assert_noop!(
+			Stake::switch_nomination(Origin::signed(9), 2, 1),
+			Error::<Test>::TooManyNominators
+		);
+		assert_ok!(Stake::nominator_bond_more(Origin::signed(9), 2, 5));
+		assert_ok!(Stake::switch_nomination(Origin::signed(9), 2, 1));
+		// 6, 7 and 10 nominate 10, the nomination of the smallest account is evicted
+		let expected = vec![
+			Event::NominationEvicted(6, 1, 10),
+			Event::NominatorLeft(6, 10),
+			Event::NominationUnbonding(6, 1, 10, 3),
+			Event::NominationSwapped(9, 15, 2, 1),
+		];
+		let mut recent = events();
+		assert_eq!(recent.split_off(recent.len() - 4), expected);
+		assert!(!Stake::is_nominator(&6));
+		let state = Stake::candidate_state(1).unwrap();
+		assert_eq!(state.nominators.0.len(), 4);
+		assert!(!state.nominators.0.iter().any(|x| x.owner == 6));
+		assert_eq!(state.total, 70);
+		assert_eq!(Stake::candidate_state(2).unwrap().total, 30);
+		assert_eq!(<Total<Test>>::get(), 150);
+		// the evicted stake stays reserved until it is unbonded
+		assert_eq!(Balances::reserved_balance(&6), 10);
+		roll_to(11);
+		assert_eq!(Balances::reserved_balance(&6), 0);
+	});
+}
+
+#[test]
+fn candidates_publish_metadata_with_a_deposit() {
+	one_validator_two_nominators().execute_with(|| {
+		assert_noop!(
+			Stake::set_metadata(Origin::signed(2), vec![], vec![], vec![], vec![]),
+			Error::<Test>::CandidateDNE
+		);
+		assert_noop!(
+			Stake::set_metadata(Origin::signed(1), vec![0; 9], vec![], vec![], vec![]),
+			Error::<Test>::MetadataTooLong
+		);
+		assert_noop!(
+			Stake::set_metadata(Origin::signed(1), vec![], vec![], vec![], vec![0; 17]),
+			Error::<Test>::MetadataTooLong
+		);
+		assert_ok!(Stake::set_metadata(
+			Origin::signed(1),
+			b"one".to_vec(),
+			b"one.io".to_vec(),
+			b"@one".to_vec(),
+			b"{}".to_vec()
+		));
+		assert_eq!(last_event(), MetaEvent::stake(Event::MetadataSet(1, 10)));
+		assert_eq!(Balances::reserved_balance(&1), 30);
+		// updating the metadata does not reserve another deposit
+		assert_ok!(Stake::set_metadata(
+			Origin::signed(1),
+			b"uno".to_vec(),
+			vec![],
+			vec![],
+			vec![]
+		));
+		assert_eq!(Balances::reserved_balance(&1), 30);
+		assert_eq!(
+			Stake::candidate_metadata(1),
+			Some(CandidateMetadata {
+				display_name: b"uno".to_vec(),
+				website: vec![],
+				contact: vec![],
+				extra: vec![],
+				deposit: 10,
+			})
+		);
+		assert_ok!(Stake::clear_metadata(Origin::signed(1)));
+		assert_eq!(last_event(), MetaEvent::stake(Event::MetadataCleared(1, 10)));
+		assert_eq!(Balances::reserved_balance(&1), 20);
+		assert_noop!(
+			Stake::clear_metadata(Origin::signed(1)),
+			Error::<Test>::MetadataDNE
+		);
+		// the deposit is returned when the candidate leaves
+		assert_ok!(Stake::set_metadata(
+			Origin::signed(1),
+			b"one".to_vec(),
+			vec![],
+			vec![],
+			vec![]
+		));
+		assert_ok!(Stake::leave_candidates(Origin::signed(1)));
+		roll_to(11);
+		assert!(events().contains(&Event::MetadataCleared(1, 10)));
+		assert!(Stake::candidate_metadata(1).is_none());
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 100);
+	});
+}
+
+#[test]
+fn controller_signs_operational_calls_of_its_stash() {
+	one_validator_two_nominators().execute_with(|| {
+		assert_noop!(
+			Stake::set_controller(Origin::signed(4), 5),
+			Error::<Test>::CandidateDNE
+		);
+		assert_noop!(
+			Stake::set_controller(Origin::signed(1), 1),
+			Error::<Test>::ControllerInUse
+		);
+		// nominators cannot be controllers
+		assert_noop!(
+			Stake::set_controller(Origin::signed(1), 2),
+			Error::<Test>::ControllerInUse
+		);
+		assert_ok!(Stake::set_controller(Origin::signed(1), 4));
+		assert_eq!(last_event(), MetaEvent::stake(Event::ControllerSet(1, 4)));
+		assert_eq!(Stake::controller(1), Some(4));
+		assert_eq!(Stake::stash_of_controller(4), Some(1));
+		assert_ok!(Stake::go_offline(Origin::signed(4)));
+		assert_eq!(Stake::candidate_state(1).unwrap().state, ValidatorStatus::Idle);
+		assert_ok!(Stake::go_online(Origin::signed(4)));
+		assert!(Stake::candidate_state(1).unwrap().is_active());
+		// the bond is reserved from the stash
+		assert_ok!(Stake::candidate_bond_more(Origin::signed(4), 10));
+		assert_eq!(Balances::reserved_balance(&1), 30);
+		assert_eq!(Balances::reserved_balance(&4), 0);
+		// and unreserved to the stash
+		assert_ok!(Stake::candidate_bond_less(Origin::signed(4), 5));
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::ValidatorBondedLess(1, 30, 25))
+		);
+		assert_eq!(Balances::reserved_balance(&1), 25);
+		assert_eq!(Balances::free_balance(&4), 100);
+		assert_noop!(
+			Stake::join_candidates(Origin::signed(4), Perbill::zero(), 10),
+			Error::<Test>::ControllerInUse
+		);
+		assert_noop!(
+			Stake::join_nominators(Origin::signed(4), 1, 10),
+			Error::<Test>::ControllerInUse
+		);
+		// a new controller replaces the previous one
+		assert_ok!(Stake::set_controller(Origin::signed(1), 5));
+		assert_eq!(Stake::stash_of_controller(4), None);
+		assert_noop!(
+			Stake::go_offline(Origin::signed(4)),
+			Error::<Test>::CandidateDNE
+		);
+	});
+}
+
+#[test]
+fn author_key_claims_blocks_for_its_stash() {
+	one_validator_two_nominators().execute_with(|| {
+		assert_noop!(
+			Stake::set_author_key(Origin::signed(1), 1),
+			Error::<Test>::AuthorKeyInUse
+		);
+		assert_ok!(Stake::set_author_key(Origin::signed(1), 6));
+		assert_eq!(last_event(), MetaEvent::stake(Event::AuthorKeySet(1, 6)));
+		// the controller can replace the author key
+		assert_ok!(Stake::set_controller(Origin::signed(1), 4));
+		assert_ok!(Stake::set_author_key(Origin::signed(4), 5));
+		assert_eq!(Stake::author_key(1), Some(5));
+		assert_eq!(Stake::stash_of_author(&5), Some(1));
+		assert_eq!(Stake::stash_of_author(&1), None);
+		assert_eq!(Stake::stash_of_author(&6), Some(6));
+		assert!(<Stake as author_inherent::CanAuthor<_>>::can_author(&5));
+		assert!(!<Stake as author_inherent::CanAuthor<_>>::can_author(&1));
+		assert!(!<Stake as author_inherent::CanAuthor<_>>::can_author(&6));
+		assert_noop!(
+			Stake::join_candidates(Origin::signed(5), Perbill::zero(), 10),
+			Error::<Test>::AuthorKeyInUse
+		);
+		// blocks claimed by the author key count for the stash
+		<Stake as author_inherent::EventHandler<_>>::note_author(5);
+		assert_eq!(Stake::uptime(1, 1).authored, 1);
+		roll_to(6);
+		assert_eq!(<AwardedPts<Test>>::get(1, 1), 1_000);
+		// the keys are released when the candidate leaves
+		assert_ok!(Stake::leave_candidates(Origin::signed(1)));
+		roll_to(16);
+		assert_eq!(Stake::controller(1), None);
+		assert_eq!(Stake::stash_of_controller(4), None);
+		assert_eq!(Stake::author_key(1), None);
+		assert_eq!(Stake::stash_of_author(&5), Some(5));
+	});
+}
+
 #[test]
 fn validators_bond() {
 	five_validators_five_nominators().execute_with(|| {
@@ -745,17 +1125,32 @@ fn nominators_bond() {
 	});
 }
 
+#[test]
+fn bonding_more_or_less_updates_total_locked() {
+	one_validator_two_nominators().execute_with(|| {
+		assert_eq!(<Total<Test>>::get(), 40);
+		assert_ok!(Stake::candidate_bond_more(Origin::signed(1), 10));
+		assert_eq!(<Total<Test>>::get(), 50);
+		assert_ok!(Stake::candidate_bond_less(Origin::signed(1), 5));
+		assert_eq!(<Total<Test>>::get(), 45);
+		assert_ok!(Stake::nominator_bond_more(Origin::signed(2), 1, 5));
+		assert_eq!(<Total<Test>>::get(), 50);
+		assert_ok!(Stake::nominator_bond_less(Origin::signed(2), 1, 5));
+		assert_eq!(<Total<Test>>::get(), 45);
+	});
+}
+
 #[test]
 fn switch_nomination() {
 	five_validators_five_nominators().execute_with(|| {
 		roll_to(8);
 		let mut expected = vec![
-			RawEvent::ValidatorChosen(2, 1, 50),
-			RawEvent::ValidatorChosen(2, 2, 40),
-			RawEvent::ValidatorChosen(2, 4, 20),
-			RawEvent::ValidatorChosen(2, 3, 20),
-			RawEvent::ValidatorChosen(2, 5, 10),
-			RawEvent::NewRound(5, 2, 5, 140),
+			Event::ValidatorChosen(2, 1, 50),
+			Event::ValidatorChosen(2, 2, 40),
+			Event::ValidatorChosen(2, 3, 20),
+			Event::ValidatorChosen(2, 4, 20),
+			Event::ValidatorChosen(2, 5, 10),
+			Event::NewRound(5, 2, 5, 140),
 		];
 		assert_eq!(events(), expected);
 		assert_noop!(
@@ -777,19 +1172,19 @@ fn switch_nomination() {
 		assert_ok!(Stake::switch_nomination(Origin::signed(6), 1, 2));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::NominationSwapped(6, 10, 1, 2))
+			MetaEvent::stake(Event::NominationSwapped(6, 10, 1, 2))
 		);
 		assert_ok!(Stake::switch_nomination(Origin::signed(7), 1, 2));
 		assert_ok!(Stake::switch_nomination(Origin::signed(8), 2, 1));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::NominationSwapped(8, 10, 2, 1))
+			MetaEvent::stake(Event::NominationSwapped(8, 10, 2, 1))
 		);
 		assert_ok!(Stake::switch_nomination(Origin::signed(9), 2, 1));
 		assert_ok!(Stake::switch_nomination(Origin::signed(10), 1, 2));
 		assert_eq!(
 			last_event(),
-			MetaEvent::stake(RawEvent::NominationSwapped(10, 10, 1, 2))
+			MetaEvent::stake(Event::NominationSwapped(10, 10, 1, 2))
 		);
 		// verify nothing changed with roles or balances since genesis
 		for x in 1..5 {
@@ -808,29 +1203,42 @@ fn switch_nomination() {
 		roll_to(10);
 		roll_to(16);
 		let mut new = vec![
-			RawEvent::NominationSwapped(6, 10, 1, 2),
-			RawEvent::NominationSwapped(7, 10, 1, 2),
-			RawEvent::NominationSwapped(8, 10, 2, 1),
-			RawEvent::NominationSwapped(9, 10, 2, 1),
-			RawEvent::NominationSwapped(10, 10, 1, 2),
-			RawEvent::ValidatorChosen(3, 2, 50),
-			RawEvent::ValidatorChosen(3, 1, 40),
-			RawEvent::ValidatorChosen(3, 4, 20),
-			RawEvent::ValidatorChosen(3, 3, 20),
-			RawEvent::ValidatorChosen(3, 5, 10),
-			RawEvent::NewRound(10, 3, 5, 140),
-			RawEvent::ValidatorChosen(4, 2, 50),
-			RawEvent::ValidatorChosen(4, 1, 40),
-			RawEvent::ValidatorChosen(4, 4, 20),
-			RawEvent::ValidatorChosen(4, 3, 20),
-			RawEvent::ValidatorChosen(4, 5, 10),
-			RawEvent::NewRound(15, 4, 5, 140),
+			Event::NominationSwapped(6, 10, 1, 2),
+			Event::NominationSwapped(7, 10, 1, 2),
+			Event::NominationSwapped(8, 10, 2, 1),
+			Event::NominationSwapped(9, 10, 2, 1),
+			Event::NominationSwapped(10, 10, 1, 2),
+			Event::ValidatorChosen(3, 2, 50),
+			Event::ValidatorChosen(3, 1, 40),
+			Event::ValidatorChosen(3, 3, 20),
+			Event::ValidatorChosen(3, 4, 20),
+			Event::ValidatorChosen(3, 5, 10),
+			Event::NewRound(10, 3, 5, 140),
+			Event::ValidatorChosen(4, 2, 50),
+			Event::ValidatorChosen(4, 1, 40),
+			Event::ValidatorChosen(4, 3, 20),
+			Event::ValidatorChosen(4, 4, 20),
+			Event::ValidatorChosen(4, 5, 10),
+			Event::NewRound(15, 4, 5, 140),
 		];
 		expected.append(&mut new);
 		assert_eq!(events(), expected);
 	});
 }
 
+#[test]
+fn switch_into_existing_nomination_merges_both() {
+	five_validators_five_nominators().execute_with(|| {
+		assert_ok!(Stake::nominate_new(Origin::signed(6), 2, 10));
+		assert_ok!(Stake::switch_nomination(Origin::signed(6), 1, 2));
+		let nominator = Stake::nominator_state(6).unwrap();
+		assert_eq!(nominator.nominations.0, vec![Bond { owner: 2, amount: 20 }]);
+		assert_eq!(nominator.total, 20);
+		assert_eq!(Stake::candidate_state(1).unwrap().total, 40);
+		assert_eq!(Stake::candidate_state(2).unwrap().total, 60);
+	});
+}
+
 #[test]
 fn revoke_nomination_or_leave_nominators() {
 	five_validators_five_nominators().execute_with(|| {
@@ -843,7 +1251,7 @@ fn revoke_nomination_or_leave_nominators() {
 			Stake::revoke_nomination(Origin::signed(6), 2),
 			Error::<Test>::NominationDNE
 		);
-		// must leave set of nominators if total bonds below MinNominatorStk
+		// must leave set of nominators if total bonds below min_nominator_stk
 		assert_noop!(
 			Stake::revoke_nomination(Origin::signed(6), 1),
 			Error::<Test>::NomBondBelowMin
@@ -868,35 +1276,36 @@ fn payouts_follow_nomination_changes() {
 		roll_to(8);
 		// chooses top MaxValidators (5), in order
 		let mut expected = vec![
-			RawEvent::ValidatorChosen(2, 1, 50),
-			RawEvent::ValidatorChosen(2, 2, 40),
-			RawEvent::ValidatorChosen(2, 4, 20),
-			RawEvent::ValidatorChosen(2, 3, 20),
-			RawEvent::ValidatorChosen(2, 5, 10),
-			RawEvent::NewRound(5, 2, 5, 140),
+			Event::ValidatorChosen(2, 1, 50),
+			Event::ValidatorChosen(2, 2, 40),
+			Event::ValidatorChosen(2, 3, 20),
+			Event::ValidatorChosen(2, 4, 20),
+			Event::ValidatorChosen(2, 5, 10),
+			Event::NewRound(5, 2, 5, 140),
 		];
 		assert_eq!(events(), expected);
 		// ~ set block author as 1 for all blocks this round
 		set_author(2, 1, 100);
 		roll_to(16);
+		assert_ok!(Stake::claim_rewards(Origin::signed(7), 2, 1));
 		// distribute total issuance to validator 1 and its nominators 6, 7, 19
 		let mut new = vec![
-			RawEvent::ValidatorChosen(3, 1, 50),
-			RawEvent::ValidatorChosen(3, 2, 40),
-			RawEvent::ValidatorChosen(3, 4, 20),
-			RawEvent::ValidatorChosen(3, 3, 20),
-			RawEvent::ValidatorChosen(3, 5, 10),
-			RawEvent::NewRound(10, 3, 5, 140),
-			RawEvent::Rewarded(1, 20),
-			RawEvent::Rewarded(6, 10),
-			RawEvent::Rewarded(7, 10),
-			RawEvent::Rewarded(10, 10),
-			RawEvent::ValidatorChosen(4, 1, 50),
-			RawEvent::ValidatorChosen(4, 2, 40),
-			RawEvent::ValidatorChosen(4, 4, 20),
-			RawEvent::ValidatorChosen(4, 3, 20),
-			RawEvent::ValidatorChosen(4, 5, 10),
-			RawEvent::NewRound(15, 4, 5, 140),
+			Event::ValidatorChosen(3, 1, 50),
+			Event::ValidatorChosen(3, 2, 40),
+			Event::ValidatorChosen(3, 3, 20),
+			Event::ValidatorChosen(3, 4, 20),
+			Event::ValidatorChosen(3, 5, 10),
+			Event::NewRound(10, 3, 5, 140),
+			Event::ValidatorChosen(4, 1, 50),
+			Event::ValidatorChosen(4, 2, 40),
+			Event::ValidatorChosen(4, 3, 20),
+			Event::ValidatorChosen(4, 4, 20),
+			Event::ValidatorChosen(4, 5, 10),
+			Event::NewRound(15, 4, 5, 140),
+			Event::Rewarded(1, 20),
+			Event::Rewarded(6, 10),
+			Event::Rewarded(7, 10),
+			Event::Rewarded(10, 10),
 		];
 		expected.append(&mut new);
 		assert_eq!(events(), expected);
@@ -910,54 +1319,59 @@ fn payouts_follow_nomination_changes() {
 		);
 		assert_ok!(Stake::leave_nominators(Origin::signed(6)));
 		roll_to(21);
+		assert_ok!(Stake::claim_rewards(Origin::signed(7), 3, 1));
 		// keep paying 6 (note: inflation is in terms of total issuance so that's why 1 is 21)
 		let mut new2 = vec![
-			RawEvent::NominatorLeftValidator(6, 1, 10, 40),
-			RawEvent::NominatorLeft(6, 10),
-			RawEvent::Rewarded(1, 21),
-			RawEvent::Rewarded(6, 10),
-			RawEvent::Rewarded(7, 10),
-			RawEvent::Rewarded(10, 10),
-			RawEvent::ValidatorChosen(5, 2, 40),
-			RawEvent::ValidatorChosen(5, 1, 40),
-			RawEvent::ValidatorChosen(5, 4, 20),
-			RawEvent::ValidatorChosen(5, 3, 20),
-			RawEvent::ValidatorChosen(5, 5, 10),
-			RawEvent::NewRound(20, 5, 5, 130),
+			Event::NominatorLeftValidator(6, 1, 10, 40),
+			Event::NominationUnbonding(6, 1, 10, 6),
+			Event::NominatorLeft(6, 10),
+			Event::ValidatorChosen(5, 1, 40),
+			Event::ValidatorChosen(5, 2, 40),
+			Event::ValidatorChosen(5, 3, 20),
+			Event::ValidatorChosen(5, 4, 20),
+			Event::ValidatorChosen(5, 5, 10),
+			Event::NewRound(20, 5, 5, 130),
+			Event::Rewarded(1, 21),
+			Event::Rewarded(6, 10),
+			Event::Rewarded(7, 10),
+			Event::Rewarded(10, 10),
 		];
 		expected.append(&mut new2);
 		assert_eq!(events(), expected);
 		// 6 won't be paid for this round because they left already
 		set_author(5, 1, 100);
 		roll_to(26);
+		assert_ok!(Stake::claim_rewards(Origin::signed(7), 4, 1));
 		// keep paying 6
 		let mut new3 = vec![
-			RawEvent::Rewarded(1, 22),
-			RawEvent::Rewarded(6, 11),
-			RawEvent::Rewarded(7, 11),
-			RawEvent::Rewarded(10, 11),
-			RawEvent::ValidatorChosen(6, 2, 40),
-			RawEvent::ValidatorChosen(6, 1, 40),
-			RawEvent::ValidatorChosen(6, 4, 20),
-			RawEvent::ValidatorChosen(6, 3, 20),
-			RawEvent::ValidatorChosen(6, 5, 10),
-			RawEvent::NewRound(25, 6, 5, 130),
+			Event::Unbonded(6, 10),
+			Event::ValidatorChosen(6, 1, 40),
+			Event::ValidatorChosen(6, 2, 40),
+			Event::ValidatorChosen(6, 3, 20),
+			Event::ValidatorChosen(6, 4, 20),
+			Event::ValidatorChosen(6, 5, 10),
+			Event::NewRound(25, 6, 5, 130),
+			Event::Rewarded(1, 22),
+			Event::Rewarded(6, 11),
+			Event::Rewarded(7, 11),
+			Event::Rewarded(10, 11),
 		];
 		expected.append(&mut new3);
 		assert_eq!(events(), expected);
 		set_author(6, 1, 100);
 		roll_to(31);
+		assert_ok!(Stake::claim_rewards(Origin::signed(7), 5, 1));
 		// no more paying 6
 		let mut new4 = vec![
-			RawEvent::Rewarded(1, 29),
-			RawEvent::Rewarded(7, 14),
-			RawEvent::Rewarded(10, 14),
-			RawEvent::ValidatorChosen(7, 2, 40),
-			RawEvent::ValidatorChosen(7, 1, 40),
-			RawEvent::ValidatorChosen(7, 4, 20),
-			RawEvent::ValidatorChosen(7, 3, 20),
-			RawEvent::ValidatorChosen(7, 5, 10),
-			RawEvent::NewRound(30, 7, 5, 130),
+			Event::ValidatorChosen(7, 1, 40),
+			Event::ValidatorChosen(7, 2, 40),
+			Event::ValidatorChosen(7, 3, 20),
+			Event::ValidatorChosen(7, 4, 20),
+			Event::ValidatorChosen(7, 5, 10),
+			Event::NewRound(30, 7, 5, 130),
+			Event::Rewarded(1, 29),
+			Event::Rewarded(7, 14),
+			Event::Rewarded(10, 14),
 		];
 		expected.append(&mut new4);
 		assert_eq!(events(), expected);
@@ -969,52 +1383,985 @@ fn payouts_follow_nomination_changes() {
 		);
 		assert_ok!(Stake::nominate_new(Origin::signed(8), 1, 10));
 		roll_to(36);
+		assert_ok!(Stake::claim_rewards(Origin::signed(7), 6, 1));
 		// new nomination is not rewarded yet
 		let mut new5 = vec![
-			RawEvent::ValidatorNominated(8, 10, 1, 50),
-			RawEvent::Rewarded(1, 30),
-			RawEvent::Rewarded(7, 15),
-			RawEvent::Rewarded(10, 15),
-			RawEvent::ValidatorChosen(8, 1, 50),
-			RawEvent::ValidatorChosen(8, 2, 40),
-			RawEvent::ValidatorChosen(8, 4, 20),
-			RawEvent::ValidatorChosen(8, 3, 20),
-			RawEvent::ValidatorChosen(8, 5, 10),
-			RawEvent::NewRound(35, 8, 5, 140),
+			Event::ValidatorNominated(8, 10, 1, 50),
+			Event::ValidatorChosen(8, 1, 50),
+			Event::ValidatorChosen(8, 2, 40),
+			Event::ValidatorChosen(8, 3, 20),
+			Event::ValidatorChosen(8, 4, 20),
+			Event::ValidatorChosen(8, 5, 10),
+			Event::NewRound(35, 8, 5, 140),
+			Event::Rewarded(1, 30),
+			Event::Rewarded(7, 15),
+			Event::Rewarded(10, 15),
 		];
 		expected.append(&mut new5);
 		assert_eq!(events(), expected);
 		set_author(8, 1, 100);
 		roll_to(41);
+		assert_ok!(Stake::claim_rewards(Origin::signed(7), 7, 1));
 		// new nomination is still not rewarded yet
 		let mut new6 = vec![
-			RawEvent::Rewarded(1, 32),
-			RawEvent::Rewarded(7, 16),
-			RawEvent::Rewarded(10, 16),
-			RawEvent::ValidatorChosen(9, 1, 50),
-			RawEvent::ValidatorChosen(9, 2, 40),
-			RawEvent::ValidatorChosen(9, 4, 20),
-			RawEvent::ValidatorChosen(9, 3, 20),
-			RawEvent::ValidatorChosen(9, 5, 10),
-			RawEvent::NewRound(40, 9, 5, 140),
+			Event::ValidatorChosen(9, 1, 50),
+			Event::ValidatorChosen(9, 2, 40),
+			Event::ValidatorChosen(9, 3, 20),
+			Event::ValidatorChosen(9, 4, 20),
+			Event::ValidatorChosen(9, 5, 10),
+			Event::NewRound(40, 9, 5, 140),
+			Event::Rewarded(1, 32),
+			Event::Rewarded(7, 16),
+			Event::Rewarded(10, 16),
 		];
 		expected.append(&mut new6);
 		assert_eq!(events(), expected);
 		roll_to(46);
+		assert_ok!(Stake::claim_rewards(Origin::signed(7), 8, 1));
 		// new nomination is rewarded for first time, 2 rounds after joining (`BondDuration` = 2)
 		let mut new7 = vec![
-			RawEvent::Rewarded(1, 27),
-			RawEvent::Rewarded(7, 13),
-			RawEvent::Rewarded(8, 13),
-			RawEvent::Rewarded(10, 13),
-			RawEvent::ValidatorChosen(10, 1, 50),
-			RawEvent::ValidatorChosen(10, 2, 40),
-			RawEvent::ValidatorChosen(10, 4, 20),
-			RawEvent::ValidatorChosen(10, 3, 20),
-			RawEvent::ValidatorChosen(10, 5, 10),
-			RawEvent::NewRound(45, 10, 5, 140),
+			Event::ValidatorChosen(10, 1, 50),
+			Event::ValidatorChosen(10, 2, 40),
+			Event::ValidatorChosen(10, 3, 20),
+			Event::ValidatorChosen(10, 4, 20),
+			Event::ValidatorChosen(10, 5, 10),
+			Event::NewRound(45, 10, 5, 140),
+			Event::Rewarded(1, 27),
+			Event::Rewarded(7, 13),
+			Event::Rewarded(8, 13),
+			Event::Rewarded(10, 13),
 		];
 		expected.append(&mut new7);
 		assert_eq!(events(), expected);
 	});
 }
+
+#[test]
+fn offence_slashes_validator_and_nominators() {
+	two_validators_four_nominators().execute_with(|| {
+		roll_to(4);
+		assert_noop!(
+			Stake::report_offence(Origin::signed(3), 1, 1),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Stake::report_offence(Origin::root(), 3, 1),
+			Error::<Test>::NoSnapshotForOffence
+		);
+		assert_ok!(Stake::report_offence(Origin::root(), 1, 1));
+		assert_noop!(
+			Stake::report_offence(Origin::root(), 1, 1),
+			Error::<Test>::OffenceAlreadyReported
+		);
+		// slash is deferred by `SlashDeferDuration` = 1 round
+		assert_eq!(Balances::reserved_balance(&1), 500);
+		roll_to(6);
+		let expected = vec![
+			Event::OffenceReported(1, 1, Perbill::from_percent(10)),
+			Event::SlashDeferred(2, 1),
+			Event::Slashed(1, 50),
+			Event::Slashed(3, 10),
+			Event::Slashed(4, 10),
+			Event::ValidatorChosen(2, 1, 630),
+			Event::ValidatorChosen(2, 2, 400),
+			Event::NewRound(5, 2, 2, 1030),
+		];
+		assert_eq!(events(), expected);
+		assert_eq!(Balances::reserved_balance(&1), 450);
+		assert_eq!(Balances::free_balance(&1), 500);
+		for x in 3..5 {
+			assert_eq!(Balances::reserved_balance(&x), 90);
+			assert_eq!(Balances::free_balance(&x), 0);
+			assert_eq!(<Stake as Store>::Nominators::get(x).unwrap().total, 90);
+		}
+		for x in 5..7 {
+			assert_eq!(Balances::reserved_balance(&x), 100);
+		}
+		let state = <Stake as Store>::Candidates::get(&1).unwrap();
+		assert_eq!(state.bond, 450);
+		assert_eq!(state.total, 630);
+	});
+}
+
+#[test]
+fn deferred_slash_can_be_cancelled() {
+	two_validators_four_nominators().execute_with(|| {
+		assert_ok!(Stake::report_offence(Origin::root(), 1, 1));
+		assert_ok!(Stake::report_offence(Origin::root(), 2, 1));
+		assert_noop!(
+			Stake::cancel_deferred_slash(Origin::signed(1), 2, vec![0]),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Stake::cancel_deferred_slash(Origin::root(), 2, vec![]),
+			Error::<Test>::EmptySlashIndices
+		);
+		assert_noop!(
+			Stake::cancel_deferred_slash(Origin::root(), 2, vec![2]),
+			Error::<Test>::InvalidSlashIndex
+		);
+		assert_ok!(Stake::cancel_deferred_slash(Origin::root(), 2, vec![0]));
+		roll_to(6);
+		let expected = vec![
+			Event::OffenceReported(1, 1, Perbill::from_percent(10)),
+			Event::SlashDeferred(2, 1),
+			Event::OffenceReported(1, 2, Perbill::from_percent(10)),
+			Event::SlashDeferred(2, 2),
+			Event::SlashCancelled(2, 1),
+			Event::Slashed(2, 20),
+			Event::Slashed(5, 10),
+			Event::Slashed(6, 10),
+			Event::ValidatorChosen(2, 1, 700),
+			Event::ValidatorChosen(2, 2, 340),
+			Event::NewRound(5, 2, 2, 1040),
+		];
+		assert_eq!(events(), expected);
+		assert_eq!(Balances::reserved_balance(&1), 500);
+		assert_eq!(Balances::reserved_balance(&2), 180);
+	});
+}
+
+#[test]
+fn nominator_unbonding_is_delayed() {
+	one_validator_two_nominators().execute_with(|| {
+		roll_to(4);
+		assert_ok!(Stake::join_candidates(
+			Origin::signed(4),
+			Perbill::from_percent(20),
+			20u128
+		));
+		assert_ok!(Stake::nominate_new(Origin::signed(2), 4, 10));
+		assert_ok!(Stake::nominator_bond_less(Origin::signed(2), 4, 5));
+		assert_ok!(Stake::revoke_nomination(Origin::signed(2), 1));
+		assert_ok!(Stake::leave_nominators(Origin::signed(3)));
+		// unbonding stake remains reserved until `UnbondingDelay` rounds pass
+		assert_eq!(<Stake as Store>::Nominators::get(2).unwrap().total, 5);
+		assert_eq!(Balances::reserved_balance(&2), 20);
+		assert_eq!(Balances::reserved_balance(&3), 10);
+		roll_to(10);
+		assert_eq!(Balances::reserved_balance(&2), 20);
+		assert_eq!(Balances::reserved_balance(&3), 10);
+		roll_to(11);
+		let expected = vec![
+			Event::JoinedValidatorCandidates(4, 20, 60),
+			Event::ValidatorNominated(2, 10, 4, 30),
+			Event::NominationDecreased(2, 4, 30, 25),
+			Event::NominationUnbonding(2, 4, 5, 3),
+			Event::NominatorLeftValidator(2, 1, 10, 30),
+			Event::NominationUnbonding(2, 1, 10, 3),
+			Event::NominatorLeftValidator(3, 1, 10, 20),
+			Event::NominationUnbonding(3, 1, 10, 3),
+			Event::NominatorLeft(3, 10),
+			Event::ValidatorChosen(2, 4, 25),
+			Event::ValidatorChosen(2, 1, 20),
+			Event::NewRound(5, 2, 2, 45),
+			Event::Unbonded(2, 15),
+			Event::Unbonded(3, 10),
+			Event::ValidatorChosen(3, 4, 25),
+			Event::ValidatorChosen(3, 1, 20),
+			Event::NewRound(10, 3, 2, 45),
+		];
+		assert_eq!(events(), expected);
+		assert_eq!(Balances::reserved_balance(&2), 5);
+		assert_eq!(Balances::free_balance(&2), 95);
+		assert_eq!(Balances::reserved_balance(&3), 0);
+		assert_eq!(Balances::free_balance(&3), 100);
+		assert!(<Stake as Store>::Unbonding::get(2).is_empty());
+	});
+}
+
+#[test]
+fn unbonding_stake_remains_slashable() {
+	two_validators_four_nominators().execute_with(|| {
+		roll_to(4);
+		assert_ok!(Stake::leave_nominators(Origin::signed(3)));
+		// nominator 3 was exposed in round 1 so it is slashed while unbonding
+		assert_ok!(Stake::report_offence(Origin::root(), 1, 1));
+		roll_to(6);
+		assert_eq!(Balances::reserved_balance(&3), 90);
+		assert_eq!(<Stake as Store>::Unbonding::get(3)[0].amount, 90);
+		assert_eq!(
+			<Stake as Store>::Candidates::get(&1).unwrap().total,
+			540
+		);
+		roll_to(11);
+		assert_eq!(Balances::reserved_balance(&3), 0);
+		assert_eq!(Balances::free_balance(&3), 90);
+	});
+}
+
+#[test]
+fn deferred_slash_applies_after_validator_exits() {
+	two_validators_four_nominators().execute_with(|| {
+		roll_to(4);
+		assert_ok!(Stake::leave_candidates(Origin::signed(2)));
+		roll_to(11);
+		assert!(<Stake as Store>::Candidates::get(&2).is_none());
+		// the nominations of the exited validator are slashed while unbonding
+		assert_ok!(Stake::report_offence(Origin::root(), 2, 1));
+		roll_to(16);
+		let expected = vec![
+			Event::ValidatorScheduledExit(1, 2, 3),
+			Event::ValidatorChosen(2, 1, 700),
+			Event::NewRound(5, 2, 1, 700),
+			Event::NominationUnbonding(5, 2, 100, 4),
+			Event::NominationUnbonding(6, 2, 100, 4),
+			Event::ValidatorLeft(2, 400, 700),
+			Event::ValidatorChosen(3, 1, 700),
+			Event::NewRound(10, 3, 1, 700),
+			Event::OffenceReported(1, 2, Perbill::from_percent(10)),
+			Event::SlashDeferred(4, 2),
+			Event::Slashed(5, 10),
+			Event::Slashed(6, 10),
+			Event::Unbonded(5, 90),
+			Event::Unbonded(6, 90),
+			Event::ValidatorChosen(4, 1, 700),
+			Event::NewRound(15, 4, 1, 700),
+		];
+		assert_eq!(events(), expected);
+		for x in 5..7 {
+			assert_eq!(Balances::reserved_balance(&x), 0);
+			assert_eq!(Balances::free_balance(&x), 90);
+		}
+		assert_eq!(Balances::free_balance(&2), 300);
+	});
+}
+
+#[test]
+fn pending_rewards_match_payouts() {
+	one_validator_two_nominators().execute_with(|| {
+		roll_to(9);
+		assert_ok!(Stake::join_candidates(
+			Origin::signed(4),
+			Perbill::from_percent(20),
+			20u128
+		));
+		assert_ok!(Stake::join_nominators(Origin::signed(5), 4, 10));
+		assert_ok!(Stake::join_nominators(Origin::signed(6), 4, 10));
+		roll_to(11);
+		// only reward author with id 4
+		set_author(3, 4, 100);
+		roll_to(19);
+		assert_eq!(Stake::blocks_left_in_round(), 1);
+		assert_eq!(Stake::pending_rewards(3, &4), 18);
+		assert_eq!(Stake::pending_rewards(3, &5), 6);
+		assert_eq!(Stake::pending_rewards(3, &6), 6);
+		assert!(Stake::pending_rewards(3, &1).is_zero());
+		roll_to(21);
+		assert_eq!(Stake::blocks_left_in_round(), 4);
+		// the issuance is fixed once the round is claimable
+		assert_eq!(Stake::pending_rewards(3, &4), 18);
+		assert_ok!(Stake::claim_rewards(Origin::signed(1), 3, 4));
+		let rewarded = events()
+			.into_iter()
+			.filter(|e| matches!(e, Event::Rewarded(..)))
+			.collect::<Vec<Event<Test>>>();
+		assert_eq!(
+			rewarded,
+			vec![
+				Event::Rewarded(4, 18),
+				Event::Rewarded(5, 6),
+				Event::Rewarded(6, 6),
+			]
+		);
+		// nothing is pending once the rewards are claimed
+		assert!(Stake::pending_rewards(3, &4).is_zero());
+		assert!(Stake::pending_rewards(3, &5).is_zero());
+	});
+}
+
+#[test]
+fn rewards_can_be_claimed_until_they_expire() {
+	five_validators_no_nominators().execute_with(|| {
+		set_author(1, 1, 60);
+		set_author(1, 2, 40);
+		roll_to(10);
+		// round 1 is claimable once `BondDuration` rounds have started since
+		assert_noop!(
+			Stake::claim_rewards(Origin::signed(3), 1, 1),
+			Error::<Test>::RoundNotClaimable
+		);
+		roll_to(11);
+		assert!(Stake::round_issuance(1).is_some());
+		assert_noop!(
+			Stake::claim_rewards(Origin::signed(3), 1, 3),
+			Error::<Test>::RewardsDNE
+		);
+		assert_ok!(Stake::claim_rewards(Origin::signed(3), 1, 1));
+		assert!(matches!(last_event(), MetaEvent::stake(Event::Rewarded(1, _))));
+		assert_noop!(
+			Stake::claim_rewards(Origin::signed(3), 1, 1),
+			Error::<Test>::RewardsDNE
+		);
+		// unclaimed rewards expire after `RewardClaimWindow` rounds
+		roll_to(20);
+		assert!(!Stake::pending_rewards(1, &2).is_zero());
+		roll_to(21);
+		assert!(Stake::round_issuance(1).is_none());
+		assert!(Stake::pending_rewards(1, &2).is_zero());
+		assert_noop!(
+			Stake::claim_rewards(Origin::signed(3), 1, 2),
+			Error::<Test>::RoundNotClaimable
+		);
+		// the snapshot of the round is removed with the rewards
+		assert_noop!(
+			Stake::report_offence(Origin::root(), 2, 1),
+			Error::<Test>::NoSnapshotForOffence
+		);
+	});
+}
+
+#[test]
+fn points_are_awarded_from_uptime_when_the_round_ends() {
+	five_validators_no_nominators().execute_with(|| {
+		// 1 authors all of its eligible slots and 2 only one of them
+		for _ in 0..4 {
+			Stake::note_eligible(&[1, 2]);
+			<Stake as author_inherent::EventHandler<_>>::note_author(1);
+		}
+		Stake::note_eligible(&[2, 3]);
+		<Stake as author_inherent::EventHandler<_>>::note_author(2);
+		// the eligibility of 4 is not reported so its authored blocks count as eligible slots
+		<Stake as author_inherent::EventHandler<_>>::note_author(4);
+		assert_eq!(
+			Stake::uptime(1, 2),
+			UptimeInfo {
+				eligible: 5,
+				authored: 1
+			}
+		);
+		// no points are awarded before the round ends
+		assert!(!<AwardedPts<Test>>::contains_key(1, 1));
+		roll_to(6);
+		assert_eq!(<AwardedPts<Test>>::get(1, 1), 1_000);
+		assert_eq!(<AwardedPts<Test>>::get(1, 2), 200);
+		assert!(!<AwardedPts<Test>>::contains_key(1, 3));
+		assert_eq!(<AwardedPts<Test>>::get(1, 4), 1_000);
+		assert!(!<AwardedPts<Test>>::contains_key(1, 5));
+		assert_eq!(<Points<Test>>::get(1), 2_200);
+		let recorded = events()
+			.into_iter()
+			.filter(|e| matches!(e, Event::UptimeRecorded(..)))
+			.collect::<Vec<Event<Test>>>();
+		assert_eq!(
+			recorded,
+			vec![
+				Event::UptimeRecorded(1, 1, 4, 4, 1_000),
+				Event::UptimeRecorded(1, 2, 5, 1, 200),
+				Event::UptimeRecorded(1, 3, 1, 0, 0),
+				Event::UptimeRecorded(1, 4, 0, 1, 1_000),
+			]
+		);
+	});
+}
+
+#[test]
+fn validators_that_stop_authoring_are_made_idle() {
+	five_validators_no_nominators().execute_with(|| {
+		// 1 and 2 miss round 1, 3 is not eligible
+		Stake::note_eligible(&[1, 2]);
+		roll_to(6);
+		assert_eq!(Stake::missed_rounds(), vec![(1, 1), (2, 1)]);
+		// 2 authors in round 2 and 1 misses it again
+		Stake::note_eligible(&[1, 2]);
+		<Stake as author_inherent::EventHandler<_>>::note_author(2);
+		roll_to(11);
+		assert!(events().contains(&Event::ValidatorIdled(2, 1, 2)));
+		assert!(!events()
+			.iter()
+			.any(|e| matches!(e, Event::ValidatorIdled(_, 2, _))));
+		assert!(Stake::missed_rounds().is_empty());
+		assert_eq!(Stake::candidate_state(1).unwrap().state, ValidatorStatus::Idle);
+		assert!(!<CandidatePool<Test>>::contains_key(&1));
+		assert!(!Stake::is_validator(&1));
+		assert_ok!(Stake::go_online(Origin::signed(1)));
+		roll_to(16);
+		assert!(Stake::is_validator(&1));
+	});
+}
+
+#[test]
+fn history_is_pruned_incrementally_after_history_depth() {
+	five_validators_no_nominators().execute_with(|| {
+		set_author(1, 1, 20);
+		roll_to(25);
+		// the rewards of round 1 expired but its history is kept for `HistoryDepth` rounds
+		assert_eq!(Stake::round().current, 5);
+		assert!(Stake::round_issuance(1).is_none());
+		assert_eq!(Stake::oldest_round(), 1);
+		assert_eq!(<AtStake<Test>>::iter_prefix(1).count(), 5);
+		assert_eq!(<AwardedPts<Test>>::get(1, 1), 20);
+		// at most `MaxPrunedPerBlock` entries are removed in every block
+		roll_to(26);
+		assert_eq!(<AtStake<Test>>::iter_prefix(1).count(), 2);
+		roll_to(27);
+		assert_eq!(<AtStake<Test>>::iter_prefix(1).count(), 0);
+		assert!(!<AwardedPts<Test>>::contains_key(1, 1));
+		assert_eq!(<Points<Test>>::get(1), 20);
+		assert_eq!(Stake::oldest_round(), 1);
+		roll_to(28);
+		assert_eq!(<Points<Test>>::get(1), 0);
+		assert!(!<Staked<Test>>::contains_key(1));
+		assert!(Stake::round_inflation(1).is_none());
+		assert_eq!(Stake::oldest_round(), 2);
+		// the history of round 2 is kept until round 7
+		assert_eq!(<AtStake<Test>>::iter_prefix(2).count(), 5);
+		assert!(<Staked<Test>>::contains_key(2));
+	});
+}
+
+#[test]
+fn auto_compound_bonds_back_rewards() {
+	one_validator_two_nominators().execute_with(|| {
+		roll_to(9);
+		assert_ok!(Stake::join_candidates(
+			Origin::signed(4),
+			Perbill::from_percent(20),
+			20u128
+		));
+		assert_ok!(Stake::join_nominators(Origin::signed(5), 4, 10));
+		assert_ok!(Stake::join_nominators(Origin::signed(6), 4, 10));
+		roll_to(11);
+		set_author(3, 4, 100);
+		roll_to(21);
+		assert_noop!(
+			Stake::set_auto_compound(Origin::signed(7), Perbill::from_percent(50)),
+			Error::<Test>::StakerDNE
+		);
+		assert_ok!(Stake::set_auto_compound(
+			Origin::signed(4),
+			Perbill::from_percent(50)
+		));
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::AutoCompoundSet(4, Perbill::from_percent(50)))
+		);
+		assert_ok!(Stake::set_auto_compound(Origin::signed(5), Perbill::one()));
+		assert_ok!(Stake::claim_rewards(Origin::signed(1), 3, 4));
+		let paid = events()
+			.into_iter()
+			.filter(|e| matches!(e, Event::Rewarded(..) | Event::Compounded(..)))
+			.collect::<Vec<Event<Test>>>();
+		assert_eq!(
+			paid,
+			vec![
+				Event::Rewarded(4, 18),
+				Event::Compounded(4, 4, 9),
+				Event::Rewarded(5, 6),
+				Event::Compounded(5, 4, 6),
+				Event::Rewarded(6, 6),
+			]
+		);
+		let validator = Stake::candidate_state(4).unwrap();
+		assert_eq!(validator.bond, 29);
+		assert_eq!(validator.total, 55);
+		assert_eq!(Stake::candidate_pool(4), Some(55));
+		assert_eq!(Stake::nominator_state(5).unwrap().total, 16);
+		assert_eq!(Balances::reserved_balance(&4), 29);
+		assert_eq!(Balances::reserved_balance(&5), 16);
+		assert_eq!(Balances::reserved_balance(&6), 10);
+	});
+}
+
+#[test]
+fn reward_destination_redirects_rewards() {
+	one_validator_two_nominators().execute_with(|| {
+		roll_to(9);
+		assert_ok!(Stake::join_candidates(
+			Origin::signed(4),
+			Perbill::from_percent(20),
+			20u128
+		));
+		assert_ok!(Stake::join_nominators(Origin::signed(5), 4, 10));
+		assert_ok!(Stake::join_nominators(Origin::signed(6), 4, 10));
+		roll_to(11);
+		set_author(3, 4, 100);
+		roll_to(21);
+		assert_noop!(
+			Stake::set_reward_destination(Origin::signed(8), RewardDestination::Restake),
+			Error::<Test>::StakerDNE
+		);
+		// the compounded fraction is still bonded back by the staking account
+		assert_ok!(Stake::set_reward_destination(
+			Origin::signed(4),
+			RewardDestination::Account(7)
+		));
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::RewardDestinationSet(
+				4,
+				RewardDestination::Account(7)
+			))
+		);
+		assert_ok!(Stake::set_auto_compound(
+			Origin::signed(4),
+			Perbill::from_percent(50)
+		));
+		assert_ok!(Stake::set_reward_destination(
+			Origin::signed(5),
+			RewardDestination::Restake
+		));
+		assert_ok!(Stake::set_reward_destination(
+			Origin::signed(6),
+			RewardDestination::Account(1)
+		));
+		assert_ok!(Stake::claim_rewards(Origin::signed(1), 3, 4));
+		let paid = events()
+			.into_iter()
+			.filter(|e| matches!(e, Event::Rewarded(..) | Event::Compounded(..)))
+			.collect::<Vec<Event<Test>>>();
+		assert_eq!(
+			paid,
+			vec![
+				Event::Rewarded(4, 9),
+				Event::Compounded(4, 4, 9),
+				Event::Rewarded(7, 9),
+				Event::Rewarded(5, 6),
+				Event::Compounded(5, 4, 6),
+				Event::Rewarded(1, 6),
+			]
+		);
+		assert_eq!(Balances::free_balance(&4), 80);
+		assert_eq!(Balances::free_balance(&7), 9);
+		assert_eq!(Balances::free_balance(&5), 90);
+		assert_eq!(Balances::free_balance(&6), 90);
+		assert_eq!(Stake::candidate_state(4).unwrap().total, 55);
+		// leaving nominators clears their reward destination
+		assert_ok!(Stake::leave_nominators(Origin::signed(6)));
+		assert_eq!(Stake::reward_destination(6), RewardDestination::Staker);
+	});
+}
+
+#[test]
+fn parachain_bond_reserve_is_minted_before_stakers_are_paid() {
+	five_validators_no_nominators().execute_with(|| {
+		assert_noop!(
+			Stake::set_parachain_bond_account(Origin::signed(1), 11),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Stake::set_parachain_bond_reserve_percent(Origin::signed(1), Percent::from_percent(30)),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Stake::set_parachain_bond_account(Origin::root(), 11));
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::ParachainBondAccountSet(0, 11))
+		);
+		assert_ok!(Stake::set_parachain_bond_reserve_percent(
+			Origin::root(),
+			Percent::from_percent(30)
+		));
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::ParachainBondReservePercentSet(
+				Percent::zero(),
+				Percent::from_percent(30)
+			))
+		);
+		set_author(1, 1, 100);
+		roll_to(11);
+		let reserved = Balances::free_balance(&11);
+		assert!(!reserved.is_zero());
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::NewRound(10, 3, 5, 400))
+		);
+		assert!(events().contains(&Event::ReservedForParachainBond(1, 11, reserved)));
+		// stakers are paid the rest of the issuance
+		let issuance = Stake::round_issuance(1).unwrap();
+		assert_eq!(Percent::from_percent(30) * (issuance + reserved), reserved);
+		assert_ok!(Stake::claim_rewards(Origin::signed(1), 1, 1));
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::Rewarded(1, issuance))
+		);
+	});
+}
+
+#[test]
+fn set_issuance_curve_requires_monetary_policy_origin() {
+	one_validator_two_nominators().execute_with(|| {
+		assert_eq!(Stake::issuance_curve(), IssuanceCurve::Linear);
+		assert_noop!(
+			Stake::set_issuance_curve(Origin::signed(1), IssuanceCurve::PeakAtIdeal),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Stake::set_issuance_curve(
+			Origin::root(),
+			IssuanceCurve::PeakAtIdeal
+		));
+		assert_eq!(Stake::issuance_curve(), IssuanceCurve::PeakAtIdeal);
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::IssuanceCurveSet(IssuanceCurve::PeakAtIdeal))
+		);
+	});
+}
+
+#[test]
+fn migration_to_v2_adds_joined_round_to_candidates() {
+	one_validator_two_nominators().execute_with(|| {
+		assert_eq!(Stake::storage_version(), Releases::V4_0_0);
+		assert!(migrations::v2::pre_migrate::<Test>().is_err());
+		// mock the state of a chain from before storage versioning
+		let state = Stake::candidate_state(1).unwrap();
+		let old = migrations::v2::OldValidator {
+			id: state.id,
+			fee: Perbill::from_percent(5),
+			bond: state.bond,
+			nominators: state.nominators,
+			total: state.total,
+			state: state.state,
+		};
+		unhashed::put(&<Candidates<Test>>::hashed_key_for(1), &old);
+		put_v2_round_and_inflation();
+		<StorageVersion<Test>>::kill();
+		assert_eq!(Stake::storage_version(), Releases::V1_0_0);
+		frame_support::assert_ok!(migrations::v2::pre_migrate::<Test>());
+		migrations::migrate::<Test>();
+		assert_ok!(migrations::v2::post_migrate::<Test>());
+		assert_eq!(Stake::storage_version(), Releases::V4_0_0);
+		let state = Stake::candidate_state(1).unwrap();
+		assert_eq!(state.fee, Perbill::from_percent(5));
+		assert_eq!(state.bond, 20);
+		assert_eq!(state.total, 40);
+		assert_eq!(state.nominators.0.len(), 2);
+		assert_eq!(state.joined, migrations::v2::UNKNOWN_JOINED);
+		// the migration only runs once
+		migrations::migrate::<Test>();
+		assert_eq!(Stake::candidate_state(1).unwrap().fee, Perbill::from_percent(5));
+		// candidates that join after the migration record the round
+		roll_to(11);
+		assert_ok!(Stake::join_candidates(
+			Origin::signed(4),
+			Perbill::from_percent(2),
+			10u128
+		));
+		assert_eq!(Stake::candidate_state(4).unwrap().joined, 3);
+	});
+}
+
+#[test]
+fn migration_to_v2_indexes_candidate_pool_by_stake() {
+	five_validators_no_nominators().execute_with(|| {
+		// mock the candidates and the pool of a chain from before storage versioning
+		let mut pool = Vec::new();
+		for (account, state) in <Candidates<Test>>::iter() {
+			let old = migrations::v2::OldValidator {
+				id: state.id,
+				fee: state.fee,
+				bond: state.bond,
+				nominators: state.nominators,
+				total: state.total,
+				state: state.state,
+			};
+			unhashed::put(&<Candidates<Test>>::hashed_key_for(account), &old);
+			pool.push(Bond {
+				owner: account,
+				amount: state.total,
+			});
+		}
+		<CandidatePool<Test>>::remove_all();
+		<CandidatesByStake<Test>>::remove_all();
+		let old: migrations::v2::OldCandidatePool<AccountId, Balance> =
+			crate::set::OrderedSet::from(pool);
+		unhashed::put(&<CandidatePool<Test>>::final_prefix(), &old);
+		put_v2_round_and_inflation();
+		<StorageVersion<Test>>::kill();
+		frame_support::assert_ok!(migrations::v2::pre_migrate::<Test>());
+		migrations::migrate::<Test>();
+		assert_ok!(migrations::v2::post_migrate::<Test>());
+		assert_eq!(<CandidatePool<Test>>::get(3), Some(80));
+		let by_stake = <CandidatesByStake<Test>>::iter()
+			.map(|(_, account, _)| account)
+			.collect::<Vec<AccountId>>();
+		assert_eq!(by_stake, vec![1, 2, 3, 4, 5, 6]);
+		// the top candidates are still chosen after the migration
+		roll_to(6);
+		assert_eq!(Stake::validators(), vec![1, 2, 3, 4, 5]);
+	});
+}
+
+/// Amounts staked expected in the mock genesis
+fn absolute_expectations() -> Range<Balance> {
+	match Stake::inflation_config().expect {
+		Expectations::Absolute(expect) => expect,
+		Expectations::Relative(_) => panic!("mock expectations are amounts"),
+	}
+}
+
+/// Mock `Round` and `InflationConfig` as encoded before `Releases::V3_0_0`
+fn put_v2_round_and_inflation() {
+	let config = Stake::inflation_config();
+	let old = migrations::v3::OldInflationInfo {
+		expect: absolute_expectations(),
+		round: config.round,
+	};
+	unhashed::put(&<InflationConfig<Test>>::hashed_key(), &old);
+	unhashed::put(&<Round<Test>>::hashed_key(), &Stake::round().current);
+}
+
+#[test]
+fn staking_params_apply_at_next_round() {
+	five_validators_no_nominators().execute_with(|| {
+		let old = Stake::staking_params();
+		let new = StakingParams {
+			blocks_per_round: 10,
+			max_validators: 3,
+			max_nominators_per_validator: 4,
+			min_validator_stk: 20,
+			min_nominator_stk: 5,
+		};
+		assert_noop!(
+			Stake::set_staking_params(Origin::signed(1), new.clone()),
+			DispatchError::BadOrigin
+		);
+		// every parameter must be within the bounds set by the runtime
+		let out_of_bounds = vec![
+			StakingParams {
+				blocks_per_round: 0,
+				..new.clone()
+			},
+			StakingParams {
+				max_validators: 6,
+				..new.clone()
+			},
+			StakingParams {
+				max_nominators_per_validator: 0,
+				..new.clone()
+			},
+			StakingParams {
+				min_nominator_stk: 2,
+				..new.clone()
+			},
+			StakingParams {
+				min_validator_stk: 4,
+				..new.clone()
+			},
+		];
+		for params in out_of_bounds {
+			assert_noop!(
+				Stake::set_staking_params(Origin::root(), params),
+				Error::<Test>::InvalidStakingParams
+			);
+		}
+		assert_ok!(Stake::set_staking_params(Origin::root(), new.clone()));
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::StakingParamsScheduled(new.clone()))
+		);
+		// nothing changes before the next round
+		assert_eq!(Stake::staking_params(), old);
+		assert_eq!(Stake::pending_params(), Some(new.clone()));
+		assert_ok!(Stake::join_candidates(
+			Origin::signed(7),
+			Perbill::zero(),
+			10u128
+		));
+		roll_to(6);
+		assert!(events().contains(&Event::StakingParamsSet(old, new.clone())));
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::NewRound(5, 2, 3, 270))
+		);
+		assert_eq!(Stake::staking_params(), new);
+		assert_eq!(Stake::pending_params(), None);
+		assert_eq!(Stake::validators(), vec![1, 2, 3]);
+		// existing candidates below the new minimum are not removed
+		assert!(Stake::is_candidate(&7));
+		assert_noop!(
+			Stake::join_candidates(Origin::signed(8), Perbill::zero(), 10u128),
+			Error::<Test>::ValBondBelowMin
+		);
+		// rounds are 10 blocks long from now on
+		roll_to(15);
+		assert_eq!(Stake::round().current, 2);
+		roll_to(16);
+		assert_eq!(Stake::round().current, 3);
+		roll_to(26);
+		assert_eq!(Stake::round().current, 4);
+	});
+}
+
+#[test]
+fn round_length_changes_from_next_round() {
+	one_validator_two_nominators().execute_with(|| {
+		assert_eq!(Stake::round(), RoundInfo::new(1, 1, 5));
+		roll_to(6);
+		assert_eq!(Stake::round(), RoundInfo::new(2, 6, 5));
+		assert_eq!(Stake::blocks_left_in_round(), 4);
+		set_author(2, 1, 100);
+		let params = StakingParams {
+			blocks_per_round: 3,
+			..Stake::staking_params()
+		};
+		assert_ok!(Stake::set_staking_params(Origin::root(), params));
+		// the current round keeps its length
+		roll_to(10);
+		assert_eq!(Stake::round(), RoundInfo::new(2, 6, 5));
+		roll_to(11);
+		assert_eq!(Stake::round(), RoundInfo::new(3, 11, 3));
+		assert_eq!(Stake::blocks_left_in_round(), 2);
+		// round inflation is recomputed from the annual inflation for the new length
+		let config = Stake::inflation_config();
+		assert_eq!(
+			config.round,
+			inflation::annual_to_round(config.annual, 3, BlockTime::get())
+		);
+		assert_eq!(Stake::round_inflation(3), Some(config.round));
+		roll_to(14);
+		assert_eq!(Stake::round(), RoundInfo::new(4, 14, 3));
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::NewRound(13, 4, 1, 40))
+		);
+		// round 2 is paid out `BondDuration` rounds later with the inflation it started with
+		assert_eq!(Stake::round_inflation(2), Some(Perbill::from_percent(5).into()));
+		assert_eq!(
+			Stake::round_issuance(2),
+			Some(Perbill::from_percent(5) * Balances::total_issuance())
+		);
+	});
+}
+
+#[test]
+fn migration_to_v3_records_round_info() {
+	one_validator_two_nominators().execute_with(|| {
+		roll_to(8);
+		let config = Stake::inflation_config();
+		put_v2_round_and_inflation();
+		<StorageVersion<Test>>::put(Releases::V2_0_0);
+		frame_support::assert_ok!(migrations::v3::pre_migrate::<Test>());
+		migrations::migrate::<Test>();
+		assert_ok!(migrations::v3::post_migrate::<Test>());
+		assert_eq!(Stake::storage_version(), Releases::V4_0_0);
+		// round 2 started after block 5
+		assert_eq!(Stake::round(), RoundInfo::new(2, 6, 5));
+		let migrated = Stake::inflation_config();
+		assert_eq!(migrated.expect, config.expect);
+		assert_eq!(migrated.round, config.round);
+		assert_eq!(
+			migrated.annual,
+			inflation::round_to_annual(config.round, 5, BlockTime::get())
+		);
+		// the round schedule is unchanged
+		roll_to(11);
+		assert_eq!(Stake::round(), RoundInfo::new(3, 11, 5));
+	});
+}
+
+#[test]
+fn migration_to_v3_writes_staking_params_fixed_in_the_runtime() {
+	one_validator_two_nominators().execute_with(|| {
+		roll_to(8);
+		// mock a chain from before the staking parameters were stored
+		put_v2_round_and_inflation();
+		<Params<Test>>::kill();
+		<StorageVersion<Test>>::put(Releases::V2_0_0);
+		assert_eq!(Stake::staking_params().blocks_per_round, 0);
+		frame_support::assert_ok!(migrations::v3::pre_migrate::<Test>());
+		migrations::migrate::<Test>();
+		assert_ok!(migrations::v3::post_migrate::<Test>());
+		assert_eq!(Stake::staking_params(), LegacyStakingParams::get());
+		assert_eq!(Stake::round(), RoundInfo::new(2, 6, 5));
+		// rounds keep changing every `blocks_per_round` blocks
+		roll_to(11);
+		assert_eq!(Stake::round(), RoundInfo::new(3, 11, 5));
+	});
+}
+
+#[test]
+fn migration_to_v4_converts_expectations_to_amounts() {
+	one_validator_two_nominators().execute_with(|| {
+		let config = Stake::inflation_config();
+		let old = migrations::v4::OldInflationInfo {
+			expect: absolute_expectations(),
+			annual: config.annual.clone(),
+			round: config.round.clone(),
+		};
+		unhashed::put(&<InflationConfig<Test>>::hashed_key(), &old);
+		<StorageVersion<Test>>::put(Releases::V3_0_0);
+		frame_support::assert_ok!(migrations::v4::pre_migrate::<Test>());
+		migrations::migrate::<Test>();
+		assert_ok!(migrations::v4::post_migrate::<Test>());
+		assert_eq!(Stake::storage_version(), Releases::V4_0_0);
+		assert_eq!(Stake::inflation_config(), config);
+		assert_eq!(
+			Stake::inflation_config().expect,
+			Expectations::Absolute(700.into())
+		);
+	});
+}
+
+#[test]
+fn staking_expectations_can_be_relative_to_total_issuance() {
+	one_validator_two_nominators().execute_with(|| {
+		let relative = Expectations::Relative(Range {
+			min: Perbill::from_percent(10),
+			ideal: Perbill::from_percent(20),
+			max: Perbill::from_percent(50),
+		});
+		assert_noop!(
+			Stake::set_staking_expectations(Origin::signed(1), relative.clone()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Stake::set_staking_expectations(
+				Origin::root(),
+				Expectations::Relative(Range {
+					min: Perbill::from_percent(20),
+					ideal: Perbill::from_percent(10),
+					max: Perbill::from_percent(50),
+				})
+			),
+			Error::<Test>::InvalidSchedule
+		);
+		assert_ok!(Stake::set_staking_expectations(
+			Origin::root(),
+			relative.clone()
+		));
+		assert_eq!(
+			last_event(),
+			MetaEvent::stake(Event::StakeExpectationsSet(relative.clone()))
+		);
+		assert_eq!(Stake::inflation_config().expect, relative);
+		// 600 issued in the mock genesis
+		assert_eq!(
+			inflation::expected_stake_range::<Test>(&relative),
+			Range {
+				min: 60,
+				ideal: 120,
+				max: 300,
+			}
+		);
+	});
+}
+
+#[test]
+fn try_state_detects_inconsistent_stake() {
+	one_validator_two_nominators().execute_with(|| {
+		assert_ok!(Stake::do_try_state());
+		<Total<Test>>::put(0);
+		assert_eq!(
+			Stake::do_try_state(),
+			Err("total locked must be the sum of the candidate totals")
+		);
+		<Total<Test>>::put(40);
+		<CandidatePool<Test>>::remove(1);
+		assert_eq!(
+			Stake::do_try_state(),
+			Err("active candidates must be in the pool")
+		);
+		<CandidatePool<Test>>::insert(1, 40);
+		<Nominators<Test>>::mutate(2, |nominator| {
+			if let Some(nominator) = nominator {
+				nominator.total = 20;
+			}
+		});
+		assert_eq!(
+			Stake::do_try_state(),
+			Err("nominator total must be the sum of its nominations")
+		);
+	});
+}
@@ -0,0 +1,465 @@
+// Copyright 2019-2020 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for stake
+//!
+//! The values are not benchmark results: they are conservative upper bounds, rounded up from the
+//! storage accessed by each call and its per-item work, until they are replaced by the output of
+//! the benchmarks run on reference hardware with a node built with `--features runtime-benchmarks`:
+//!
+//! ./target/release/moonbeam benchmark --chain=dev --execution=wasm --wasm-execution=compiled
+//! --pallet=stake --extrinsic='*' --steps=50 --repeat=20 --output=./pallets/stake/src/weights.rs
+//!
+//! Components:
+//! * `n`: nominators of the validator that is nominated, claimed for or reported, bounded by
+//! `MaxNominatorsPerValidator`
+//! * `v`: validators nominated by the caller or chosen for the round, bounded by
+//! `MaxValidatorsPerNominator` and `MaxValidators` respectively
+//! * `s`: slash indices passed to `cancel_deferred_slash`
+//! * `p`: entries of the history removed in one block, bounded by `MaxPrunedPerBlock`
+//! * `e`: validator exits executed in the round transition
+//! * `u`: nominators whose unbonding stake is unreserved in the round transition
+//! * `d`: deferred slashes applied in the round transition
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for stake.
+pub trait WeightInfo {
+	fn set_staking_expectations() -> Weight;
+	fn set_inflation() -> Weight;
+	fn set_issuance_curve() -> Weight;
+	fn set_parachain_bond_account() -> Weight;
+	fn set_parachain_bond_reserve_percent() -> Weight;
+	fn set_staking_params() -> Weight;
+	fn join_candidates() -> Weight;
+	fn leave_candidates() -> Weight;
+	fn go_offline() -> Weight;
+	fn go_online() -> Weight;
+	fn candidate_bond_more() -> Weight;
+	fn candidate_bond_less() -> Weight;
+	fn set_commission() -> Weight;
+	fn set_metadata() -> Weight;
+	fn clear_metadata() -> Weight;
+	fn set_controller() -> Weight;
+	fn set_author_key() -> Weight;
+	fn join_nominators(n: u32) -> Weight;
+	fn leave_nominators(v: u32, n: u32) -> Weight;
+	fn nominate_new(n: u32) -> Weight;
+	fn switch_nomination(n: u32) -> Weight;
+	fn revoke_nomination(n: u32) -> Weight;
+	fn nominator_bond_more(n: u32) -> Weight;
+	fn nominator_bond_less(n: u32) -> Weight;
+	fn set_auto_compound() -> Weight;
+	fn set_reward_destination() -> Weight;
+	fn claim_rewards(n: u32) -> Weight;
+	fn report_offence(n: u32) -> Weight;
+	fn cancel_deferred_slash(s: u32) -> Weight;
+	fn round_transition(v: u32, n: u32, e: u32, u: u32, d: u32) -> Weight;
+	fn prune_history(p: u32) -> Weight;
+}
+
+/// Upper bounds of the weights for stake, see the module documentation.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn set_staking_expectations() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_inflation() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_issuance_curve() -> Weight {
+		(30_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_parachain_bond_account() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_parachain_bond_reserve_percent() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_staking_params() -> Weight {
+		(35_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn join_candidates() -> Weight {
+		(145_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn leave_candidates() -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn go_offline() -> Weight {
+		(75_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn go_online() -> Weight {
+		(75_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn candidate_bond_more() -> Weight {
+		(120_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn candidate_bond_less() -> Weight {
+		(115_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn set_commission() -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn set_metadata() -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn clear_metadata() -> Weight {
+		(80_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn set_controller() -> Weight {
+		(65_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn set_author_key() -> Weight {
+		(65_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn join_nominators(n: u32) -> Weight {
+		(170_000_000 as Weight)
+			.saturating_add((15_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(11 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+	}
+	fn leave_nominators(v: u32, n: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((84_000_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((3_000_000 as Weight).saturating_mul((v * n) as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(v as Weight)))
+	}
+	fn nominate_new(n: u32) -> Weight {
+		(170_000_000 as Weight)
+			.saturating_add((16_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+	}
+	fn switch_nomination(n: u32) -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add((12_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	fn revoke_nomination(n: u32) -> Weight {
+		(125_000_000 as Weight)
+			.saturating_add((3_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn nominator_bond_more(n: u32) -> Weight {
+		(130_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn nominator_bond_less(n: u32) -> Weight {
+		(120_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn set_auto_compound() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_reward_destination() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn claim_rewards(n: u32) -> Weight {
+		(240_000_000 as Weight)
+			.saturating_add((134_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(11 as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
+	}
+	fn report_offence(n: u32) -> Weight {
+		(105_000_000 as Weight)
+			.saturating_add((59_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+	fn cancel_deferred_slash(s: u32) -> Weight {
+		(50_000_000 as Weight)
+			.saturating_add((1_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn round_transition(v: u32, n: u32, e: u32, u: u32, d: u32) -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add((143_000_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((7_000_000 as Weight).saturating_mul((v * n) as Weight))
+			.saturating_add((100_000_000 as Weight).saturating_mul(e as Weight))
+			.saturating_add((60_000_000 as Weight).saturating_mul((e * n) as Weight))
+			.saturating_add((80_000_000 as Weight).saturating_mul(u as Weight))
+			.saturating_add((120_000_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add((80_000_000 as Weight).saturating_mul((d * n) as Weight))
+			.saturating_add(T::DbWeight::get().reads(16 as Weight))
+			.saturating_add(T::DbWeight::get().reads((8 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(e as Weight)))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul((e * n) as Weight)))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(u as Weight)))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul((d * n) as Weight)))
+			.saturating_add(T::DbWeight::get().writes(16 as Weight))
+			.saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().writes((9 as Weight).saturating_mul(e as Weight)))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul((e * n) as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(u as Weight)))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul((d * n) as Weight)))
+	}
+	fn prune_history(p: u32) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((11_000_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn set_staking_expectations() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_inflation() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_issuance_curve() -> Weight {
+		(30_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_parachain_bond_account() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_parachain_bond_reserve_percent() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_staking_params() -> Weight {
+		(35_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn join_candidates() -> Weight {
+		(145_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn leave_candidates() -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn go_offline() -> Weight {
+		(75_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn go_online() -> Weight {
+		(75_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn candidate_bond_more() -> Weight {
+		(120_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn candidate_bond_less() -> Weight {
+		(115_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn set_commission() -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn set_metadata() -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn clear_metadata() -> Weight {
+		(80_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn set_controller() -> Weight {
+		(65_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn set_author_key() -> Weight {
+		(65_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn join_nominators(n: u32) -> Weight {
+		(170_000_000 as Weight)
+			.saturating_add((15_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(11 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+	}
+	fn leave_nominators(v: u32, n: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((84_000_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((3_000_000 as Weight).saturating_mul((v * n) as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(v as Weight)))
+	}
+	fn nominate_new(n: u32) -> Weight {
+		(170_000_000 as Weight)
+			.saturating_add((16_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(10 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+	}
+	fn switch_nomination(n: u32) -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add((12_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+	}
+	fn revoke_nomination(n: u32) -> Weight {
+		(125_000_000 as Weight)
+			.saturating_add((3_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn nominator_bond_more(n: u32) -> Weight {
+		(130_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn nominator_bond_less(n: u32) -> Weight {
+		(120_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn set_auto_compound() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_reward_destination() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn claim_rewards(n: u32) -> Weight {
+		(240_000_000 as Weight)
+			.saturating_add((134_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(11 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
+	}
+	fn report_offence(n: u32) -> Weight {
+		(105_000_000 as Weight)
+			.saturating_add((59_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+	fn cancel_deferred_slash(s: u32) -> Weight {
+		(50_000_000 as Weight)
+			.saturating_add((1_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn round_transition(v: u32, n: u32, e: u32, u: u32, d: u32) -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add((143_000_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((7_000_000 as Weight).saturating_mul((v * n) as Weight))
+			.saturating_add((100_000_000 as Weight).saturating_mul(e as Weight))
+			.saturating_add((60_000_000 as Weight).saturating_mul((e * n) as Weight))
+			.saturating_add((80_000_000 as Weight).saturating_mul(u as Weight))
+			.saturating_add((120_000_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add((80_000_000 as Weight).saturating_mul((d * n) as Weight))
+			.saturating_add(RocksDbWeight::get().reads(16 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((8 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((6 as Weight).saturating_mul(e as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul((e * n) as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(u as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul((d * n) as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(16 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((9 as Weight).saturating_mul(e as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul((e * n) as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(u as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul((d * n) as Weight)))
+	}
+	fn prune_history(p: u32) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((11_000_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+	}
+}
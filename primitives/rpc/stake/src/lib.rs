@@ -0,0 +1,54 @@
+// Copyright 2019-2020 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// These clippy lints are disabled because the macro-generated code triggers them.
+#![allow(clippy::unnecessary_mut_passed)]
+#![allow(clippy::too_many_arguments)]
+
+use parity_scale_codec::{Codec, Decode, Encode};
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+pub use stake::{Bond, CandidateMetadata, Nominator, RoundIndex, Validator, ValidatorStatus};
+
+/// The current round and the number of blocks left until the next round starts
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct RoundProgress {
+	pub current: RoundIndex,
+	pub blocks_left: u32,
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait StakeRuntimeApi<AccountId, Balance> where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// State, fee, bond, total and nominators of a validator candidate
+		fn candidate_info(candidate: AccountId) -> Option<Validator<AccountId, Balance>>;
+		/// The current round and the number of blocks left in it
+		fn round_progress() -> RoundProgress;
+		/// Nominations of a nominator and their total
+		fn nominations(nominator: AccountId) -> Option<Nominator<AccountId, Balance>>;
+		/// Rewards due to an account for a round that has not been paid out yet
+		fn pending_rewards(round: RoundIndex, account: AccountId) -> Balance;
+		/// Smallest nomination that a validator candidate accepts from a new nominator
+		fn min_nomination(validator: AccountId) -> Option<Balance>;
+		/// Display name, website, contact and free-form JSON published by a validator candidate
+		fn candidate_metadata(candidate: AccountId) -> Option<CandidateMetadata<Balance>>;
+		/// Check the invariants of the stake state, returns the first one that does not hold
+		fn try_state() -> Result<(), Vec<u8>>;
+	}
+}
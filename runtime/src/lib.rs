@@ -115,10 +115,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("moonbeam"),
 	impl_name: create_runtime_str!("moonbeam"),
 	authoring_version: 3,
-	spec_version: 22,
-	impl_version: 1,
+	spec_version: 23,
+	impl_version: 2,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 2,
+	transaction_version: 3,
 };
 
 /// The version information used to identify this runtime when compiled natively.
@@ -379,36 +379,79 @@ impl parachain_info::Config for Runtime {}
 pub const GLMR: Balance = 1_000_000_000_000_000_000;
 
 parameter_types! {
-	/// Moonbeam starts a new round every hour (600 * block_time)
-	pub const BlocksPerRound: u32 = 600;
+	/// Blocks are expected every slot, which is twice the minimum period (6 seconds)
+	pub const BlockTime: u32 = (2 * MINIMUM_PERIOD / 1_000) as u32;
 	/// Reward payments and validator exit requests are delayed by 2 hours (2 * 600 * block_time)
 	pub const BondDuration: u32 = 2;
-	/// Maximum 8 valid block authors at any given time
+	/// Rewards can be claimed for a week after they become claimable (168 * 600 * block_time)
+	pub const RewardClaimWindow: u32 = 168;
+	/// The history of a round is kept for two weeks (336 * 600 * block_time)
+	pub const HistoryDepth: u32 = 336;
+	/// At most 64 entries of the history of old rounds are removed in every block
+	pub const MaxPrunedPerBlock: u32 = 64;
+	/// Nominations that are revoked or decreased are unreserved after 2 hours (2 * 600 * block_time)
+	pub const UnbondingDelay: u32 = 2;
+	/// Governance can allow at most 8 valid block authors at any given time
 	pub const MaxValidators: u32 = 8;
-	/// Maximum 10 nominators per validator
+	/// Governance can allow at most 10 nominators per validator
 	pub const MaxNominatorsPerValidator: u32 = 10;
 	/// Maximum 8 validators per nominator (same as MaxValidators)
 	pub const MaxValidatorsPerNominator: u32 = 8;
 	/// The maximum percent a validator can take off the top of its rewards is 50%
 	pub const MaxFee: Perbill = Perbill::from_percent(50);
-	/// Minimum stake required to be reserved to be a validator is 1_000
-	pub const MinValidatorStk: u128 = 1_000 * GLMR;
-	/// Minimum stake required to be reserved to be a nominator is 5
-	pub const MinNominatorStk: u128 = 5 * GLMR;
+	/// Commission changes apply a day after they are requested (24 * 600 * block_time)
+	pub const CommissionChangeDelay: u32 = 24;
+	/// Governance cannot lower the minimum nomination or nominator stake below 5
+	pub const MinNomination: u128 = 5 * GLMR;
+	/// Staking parameters fixed in the runtime before governance could set them, written to
+	/// storage by the migration of the stake pallet
+	pub const LegacyStakingParams: stake::StakingParams<Balance> = stake::StakingParams {
+		blocks_per_round: 600,
+		max_validators: 8,
+		max_nominators_per_validator: 10,
+		min_validator_stk: 1_000 * GLMR,
+		min_nominator_stk: 5 * GLMR,
+	};
+	/// Offences slash 10% of the bond and nominations of the offender
+	pub const SlashFraction: Perbill = Perbill::from_percent(10);
+	/// Slashes are applied 1 round after being reported, so they can be cancelled in between
+	pub const SlashDeferDuration: u32 = 1;
+	/// Validators that author no block for 3 rounds in a row are made idle (3 * 600 * block_time)
+	pub const MaxMissedRounds: u32 = 3;
+	/// Candidates reserve 10 GLMR to publish their metadata
+	pub const MetadataDeposit: u128 = 10 * GLMR;
+	/// The display name, website and contact of a candidate are at most 64 bytes each
+	pub const MaxMetadataFieldLength: u32 = 64;
+	/// The free-form JSON in the metadata of a candidate is at most 1 KiB
+	pub const MaxMetadataExtraLength: u32 = 1024;
 }
 impl stake::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
 	type SetMonetaryPolicyOrigin = frame_system::EnsureRoot<AccountId>;
-	type BlocksPerRound = BlocksPerRound;
+	type BlockTime = BlockTime;
 	type BondDuration = BondDuration;
+	type RewardClaimWindow = RewardClaimWindow;
+	type HistoryDepth = HistoryDepth;
+	type MaxPrunedPerBlock = MaxPrunedPerBlock;
+	type UnbondingDelay = UnbondingDelay;
 	type MaxValidators = MaxValidators;
 	type MaxNominatorsPerValidator = MaxNominatorsPerValidator;
 	type MaxValidatorsPerNominator = MaxValidatorsPerNominator;
 	type MaxFee = MaxFee;
-	type MinValidatorStk = MinValidatorStk;
-	type MinNomination = MinNominatorStk;
-	type MinNominatorStk = MinNominatorStk;
+	type CommissionChangeDelay = CommissionChangeDelay;
+	type MinNomination = MinNomination;
+	type LegacyStakingParams = LegacyStakingParams;
+	type Slash = ();
+	type ReportOrigin = EnsureRoot<AccountId>;
+	type SlashCancelOrigin = EnsureRoot<AccountId>;
+	type SlashFraction = SlashFraction;
+	type SlashDeferDuration = SlashDeferDuration;
+	type MaxMissedRounds = MaxMissedRounds;
+	type MetadataDeposit = MetadataDeposit;
+	type MaxMetadataFieldLength = MaxMetadataFieldLength;
+	type MaxMetadataExtraLength = MaxMetadataExtraLength;
+	type WeightInfo = stake::weights::SubstrateWeight<Runtime>;
 }
 impl author_inherent::Config for Runtime {
 	type EventHandler = Stake;
@@ -446,6 +489,8 @@ construct_runtime! {
 		Democracy: pallet_democracy::{Module, Storage, Config, Event<T>, Call},
 		// The order matters here. Inherents will be included in the order specified here.
 		// Concretely we need the author inherent to come after the parachain_upgrade inherent.
+		// Hooks are executed in the reverse order, so the author filter records the eligible
+		// authors of a block in its `on_finalize` before the stake pallet changes the round.
 		AuthorInherent: author_inherent::{Module, Call, Storage, Inherent},
 		AuthorFilter: pallet_author_filter::{Module, Call, Storage, Event<T>,}
 	}
@@ -578,6 +623,39 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl moonbeam_rpc_primitives_stake::StakeRuntimeApi<Block, AccountId, Balance> for Runtime {
+		fn candidate_info(candidate: AccountId) -> Option<stake::Validator<AccountId, Balance>> {
+			Stake::candidate_state(candidate)
+		}
+
+		fn round_progress() -> moonbeam_rpc_primitives_stake::RoundProgress {
+			moonbeam_rpc_primitives_stake::RoundProgress {
+				current: Stake::round().current,
+				blocks_left: Stake::blocks_left_in_round(),
+			}
+		}
+
+		fn nominations(nominator: AccountId) -> Option<stake::Nominator<AccountId, Balance>> {
+			Stake::nominator_state(nominator)
+		}
+
+		fn pending_rewards(round: stake::RoundIndex, account: AccountId) -> Balance {
+			Stake::pending_rewards(round, &account)
+		}
+
+		fn min_nomination(validator: AccountId) -> Option<Balance> {
+			Stake::min_nomination_to_enter(&validator)
+		}
+
+		fn candidate_metadata(candidate: AccountId) -> Option<stake::CandidateMetadata<Balance>> {
+			Stake::candidate_metadata(candidate)
+		}
+
+		fn try_state() -> Result<(), Vec<u8>> {
+			Stake::do_try_state().map_err(|e| e.as_bytes().to_vec())
+		}
+	}
+
 	impl fp_rpc::EthereumRuntimeRPCApi<Block> for Runtime {
 		fn chain_id() -> u64 {
 			<Runtime as pallet_evm::Config>::ChainId::get()
@@ -710,6 +788,42 @@ impl_runtime_apis! {
 			TransactionPayment::query_fee_details(uxt, len)
 		}
 	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	impl frame_benchmarking::Benchmark<Block> for Runtime {
+		fn dispatch_benchmark(
+			config: frame_benchmarking::BenchmarkConfig
+		) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+			use frame_benchmarking::{add_benchmark, BenchmarkBatch, Benchmarking, TrackedStorageKey};
+
+			let whitelist: Vec<TrackedStorageKey> = vec![
+				// Block Number
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac")
+					.to_vec().into(),
+				// Total Issuance
+				hex_literal::hex!("c2261276cc9d1f8598ea4b6a74b15c2f57c875e4cff74148e4628f264b974c80")
+					.to_vec().into(),
+				// Execution Phase
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef7ff553b5a9862a516939d82b3d3d8661a")
+					.to_vec().into(),
+				// Event Count
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef70a98fdbe9ce6c55837576c60c7af3850")
+					.to_vec().into(),
+				// System Events
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7")
+					.to_vec().into(),
+			];
+
+			let mut batches = Vec::<BenchmarkBatch>::new();
+			let params = (&config, &whitelist);
+			add_benchmark!(params, batches, stake, Stake);
+
+			if batches.is_empty() {
+				return Err("Benchmark not found for this pallet.".into());
+			}
+			Ok(batches)
+		}
+	}
 }
 
 cumulus_runtime::register_validate_block!(Block, Executive);
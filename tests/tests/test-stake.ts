@@ -1,8 +1,9 @@
 import { expect } from "chai";
 import { step } from "mocha-steps";
 
-import { describeWithMoonbeam } from "./util";
-import { GLMR } from "./constants";
+import { Keyring } from "@polkadot/keyring";
+import { createAndFinalizeBlock, customRequest, describeWithMoonbeam } from "./util";
+import { GENESIS_ACCOUNT_PRIVATE_KEY, GLMR } from "./constants";
 
 import BigNumber from "bignumber.js";
 
@@ -29,15 +30,21 @@ describeWithMoonbeam("Moonbeam RPC (Stake)", `simple-specs.json`, (context) => {
     const inflationInfo = await context.polkadotApi.query.stake.inflationConfig();
     // {
     //   expect: {
-    //     min: '100.0000 kUnit',
-    //     ideal: '200.0000 kUnit',
-    //     max: '500.0000 kUnit'
+    //     Absolute: {
+    //       min: '100.0000 kUnit',
+    //       ideal: '200.0000 kUnit',
+    //       max: '500.0000 kUnit'
+    //     }
     //   },
+    //   annual: { min: '4.00%', ideal: '5.00%', max: '5.00%' },
     //   round: { min: '0.00%', ideal: '0.00%', max: '0.00%' }
     // }
-    expect(inflationInfo.toHuman()["expect"]["min"]).to.eq("100.0000 kUnit");
-    expect(inflationInfo.toHuman()["expect"]["ideal"]).to.eq("200.0000 kUnit");
-    expect(inflationInfo.toHuman()["expect"]["max"]).to.eq("500.0000 kUnit");
+    expect(inflationInfo.toHuman()["expect"]["Absolute"]["min"]).to.eq("100.0000 kUnit");
+    expect(inflationInfo.toHuman()["expect"]["Absolute"]["ideal"]).to.eq("200.0000 kUnit");
+    expect(inflationInfo.toHuman()["expect"]["Absolute"]["max"]).to.eq("500.0000 kUnit");
+    expect(inflationInfo.toHuman()["annual"]["min"]).to.eq("4.00%");
+    expect(inflationInfo.toHuman()["annual"]["ideal"]).to.eq("5.00%");
+    expect(inflationInfo.toHuman()["annual"]["max"]).to.eq("5.00%");
     expect(inflationInfo.toHuman()["round"]["min"]).to.eq("0.00%");
     expect(Number(inflationInfo["round"]["min"])).to.eq(4563); // 4% / 8766 * 10^9
     expect(inflationInfo.toHuman()["round"]["ideal"]).to.eq("0.00%");
@@ -45,4 +52,32 @@ describeWithMoonbeam("Moonbeam RPC (Stake)", `simple-specs.json`, (context) => {
     expect(inflationInfo.toHuman()["round"]["max"]).to.eq("0.00%");
     expect(Number(inflationInfo["round"]["max"])).to.eq(5703); // 5% / 8766 * 10^9
   });
+
+  step("no candidate metadata in genesis", async function () {
+    const response = await customRequest(context.web3, "stake_candidateMetadata", [
+      GENESIS_ACCOUNT,
+    ]);
+    expect(response.result).to.be.null;
+  });
+
+  step("candidate metadata is returned by the stake rpc", async function () {
+    this.timeout(15000);
+    const keyring = new Keyring({ type: "ethereum" });
+    const candidate = await keyring.addFromUri(GENESIS_ACCOUNT_PRIVATE_KEY, null, "ethereum");
+    await context.polkadotApi.tx.stake
+      .setMetadata("genesis", "moonbeam.network", "@genesis", "{}")
+      .signAndSend(candidate);
+    await createAndFinalizeBlock(context.polkadotApi);
+
+    const response = await customRequest(context.web3, "stake_candidateMetadata", [
+      GENESIS_ACCOUNT,
+    ]);
+    expect(response.result).to.deep.equal({
+      displayName: "genesis",
+      website: "moonbeam.network",
+      contact: "@genesis",
+      extra: "{}",
+      deposit: "0x" + (10n * GLMR).toString(16),
+    });
+  });
 });